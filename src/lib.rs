@@ -2,21 +2,670 @@ pub mod syn_parse;
 
 #[cfg(test)]
 mod tests {
-    use crate::syn_parse::{syn_parse};
+    use crate::syn_parse::{syn_parse, header_gen_str, ParseResult, EventItem, PtrKind};
     use std::path::PathBuf;
+    use std::sync::Arc;
+    use rustviz_lib::data::ResourceAccessPoint;
+
+    fn fixture(name: &str) -> ParseResult {
+        // parse tests/fixtures/<name>
+        let file_name = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name);
+        match syn_parse(&file_name) {
+            Ok(res) => res,
+            Err(e) => panic!("error parsing {:?}: {:?}", file_name, e),
+        }
+    }
+
+    fn events(res: &ParseResult) -> Vec<String> {
+        // events as "line Kind from->to", lines outside of the
+        // input file as "file.rs:line"
+        let name = |rap: &Option<Arc<ResourceAccessPoint>>| match rap {
+            Some(rap) => rap.name().clone(),
+            None => String::from("None"),
+        };
+        res.events.iter().map(|(file, line, event)| {
+            let line = match file.file_name() {
+                Some(name) if *file != res.files[0] => format!("{}:{}", name.to_string_lossy(), line),
+                _ => line.to_string(),
+            };
+            let (kind, from, to) = match event {
+                EventItem::PassByMoveTo { from, to } => ("PassByMoveTo", from, to),
+                EventItem::PassByStaticReference { from, to } => ("PassByStaticReference", from, to),
+                EventItem::PassByMutableReference { from, to } => ("PassByMutableReference", from, to),
+                EventItem::Copy { from, to } => ("Copy", from, to),
+                EventItem::StaticBorrow { from, to } => ("StaticBorrow", from, to),
+                EventItem::MutableBorrow { from, to } => ("MutableBorrow", from, to),
+                EventItem::StaticDie { from, to } => ("StaticDie", from, to),
+                EventItem::MutableDie { from, to } => ("MutableDie", from, to),
+                EventItem::Move { from, to } => ("Move", from, to),
+                EventItem::SharedClone { from, to } => ("SharedClone", from, to),
+                EventItem::InteriorBorrow { from, to } => ("InteriorBorrow", from, to),
+                EventItem::InteriorMutBorrow { from, to } => ("InteriorMutBorrow", from, to),
+                EventItem::EarlyReturn { from, to } => ("EarlyReturn", from, to),
+                EventItem::RawPointer { from, to } => ("RawPointer", from, to),
+                EventItem::HeapAlloc { ro } => return format!("{} HeapAlloc {}", line, ro.name()),
+                EventItem::GoOutOfScope { ro } => return format!("{} GoOutOfScope {}", line, ro.name()),
+            };
+            format!("{} {} {}->{}", line, kind, name(from), name(to))
+        }).collect()
+    }
+
+    #[test]
+    fn call_args() {
+        let res = fixture("call_args.rs");
+        // x is copied into takes(), s is borrowed twice and then moved;
+        // the unused reference parameters die where they are declared
+        assert_eq!(events(&res), vec![
+            "1 GoOutOfScope n",
+            "2 GoOutOfScope s",
+            "3 StaticDie s->None",
+            "3 GoOutOfScope s",
+            "4 MutableDie s->None",
+            "4 GoOutOfScope s",
+            "7 Copy x->n",
+            "9 PassByStaticReference s->s",
+            "10 PassByMutableReference s->s",
+            "11 PassByMoveTo s->s",
+            "13 GoOutOfScope x",
+        ]);
+    }
+
+    #[test]
+    fn method_receivers() {
+        let res = fixture("method_recv.rs");
+        // Option<i32> is Copy: unwrap() copies o, o stays usable;
+        // Stack::len(self) does not change how Vec::len() takes v
+        assert_eq!(events(&res), vec![
+            "3 GoOutOfScope self",
+            "7 Copy o->unwrap",
+            "8 PassByStaticReference o->is_some",
+            "10 PassByStaticReference v->len",
+            "12 PassByMoveTo st->Stack::len()",
+            "14 PassByMoveTo s->into_bytes",
+            "15 GoOutOfScope b",
+            "15 GoOutOfScope v",
+            "15 GoOutOfScope o",
+        ]);
+    }
+
+    #[test]
+    fn reference_last_use() {
+        let res = fixture("ref_lifetime.rs");
+        // r dies at its last use on line 4, so &mut s on line 5 is fine;
+        // q is last used inside assert_eq! on line 10
+        assert_eq!(events(&res), vec![
+            "3 StaticBorrow s->r",
+            "4 PassByStaticReference r->len",
+            "4 StaticDie r->s",
+            "5 MutableBorrow s->m",
+            "6 PassByMutableReference m->push",
+            "6 MutableDie m->s",
+            "8 StaticBorrow s->q",
+            "10 PassByStaticReference q->len",
+            "10 StaticDie q->s",
+            "11 GoOutOfScope u",
+            "11 GoOutOfScope q",
+            "11 GoOutOfScope m",
+            "11 GoOutOfScope n",
+            "11 GoOutOfScope r",
+            "11 GoOutOfScope s",
+        ]);
+    }
+
+    #[test]
+    fn drop_order() {
+        let res = fixture("drop_order.rs");
+        // reverse declaration order at each closing brace; x was only
+        // copied and is still dropped, b, a and inner were moved out
+        assert_eq!(events(&res), vec![
+            "1 GoOutOfScope n",
+            "2 GoOutOfScope s",
+            "7 Copy x->n",
+            "8 PassByMoveTo b->s",
+            "11 GoOutOfScope c",
+            "12 Move a->d",
+            "15 Move inner->y",
+            "17 Copy x->z",
+            "18 GoOutOfScope z",
+            "18 GoOutOfScope y",
+            "18 GoOutOfScope d",
+            "18 GoOutOfScope x",
+        ]);
+    }
+
+    #[test]
+    fn multi_file_mods() {
+        let res = fixture("multi_file/main.rs");
+        assert_eq!(res.files.len(), 2);
+        assert!(res.files[1].ends_with("multi_file/shapes.rs"));
+        assert_eq!(res.diagnostics, vec![
+            (res.files[0].clone(), 2, String::from("file for mod missing not found")),
+        ]);
+        // measure() is called through a `use` declared below main();
+        // the events of shapes.rs keep their own file and lines
+        assert_eq!(events(&res), vec![
+            "5 PassByStaticReference s->s",
+            "6 GoOutOfScope s",
+            "shapes.rs:2 PassByStaticReference s->len",
+            "shapes.rs:2 StaticDie s->None",
+            "shapes.rs:3 GoOutOfScope s",
+        ]);
+    }
 
     #[test]
-     fn test() {
-        // let file_source = PathBuf::from("/Users/haochenz/Desktop/rustviz/src/examples/struct_rect/source.rs");
-        // let annotated_source = PathBuf::from("/Users/haochenz/Desktop/rustviz/src/examples/struct_rect/input/annotated_source.rs");
-        let fname =  PathBuf::from("");
-        let parse_res = syn_parse(&file_name);
-        match parse_res {
-            Ok((rap, color_info)) => {
-                println!("{:?}", rap);
-                println!("{:?}", color_info);
-            }
-            Err(e) => println!("error parsing header: {:?}", e),
+    fn struct_kinds() {
+        let res = fixture("struct_kinds.rs");
+        let wrapper = &res.structs["Wrapper"];
+        assert_eq!(wrapper.generics, vec![String::from("'a"), String::from("T")]);
+        assert_eq!(wrapper.fields, vec![
+            (String::from("val"), String::from("&'a T")),
+            (String::from("n"), String::from("i32")),
+        ]);
+        assert_eq!(res.structs["Meters"].fields, vec![(String::from("0"), String::from("f64"))]);
+        assert!(res.structs["Marker"].fields.is_empty());
+        // tuple struct literal and unit struct both make struct owners
+        assert!(res.color_info.iter().any(|stack| stack.contains_key("m.0")));
+        assert_eq!(events(&res), vec!["7 GoOutOfScope k", "7 GoOutOfScope m"]);
+    }
+
+    #[test]
+    fn struct_update_syntax() {
+        let res = fixture("struct_update.rs");
+        assert_eq!(events(&res), vec![
+            // shorthand fields
+            "8 Move name->name",
+            "8 Copy level->level",
+            // ..base: Copy fields are copied, &'a mut fields are moved
+            "9 Copy level->level",
+            "9 Move out->out",
+            "9 Copy tag->tag",
+            // ..origin() has no RAP to take y from: no event
+            "11 GoOutOfScope q",
+            "11 GoOutOfScope c",
+            "11 GoOutOfScope base",
+            "11 GoOutOfScope level",
+            "11 GoOutOfScope o",
+        ]);
+    }
+
+    #[test]
+    fn struct_temporaries() {
+        let res = fixture("struct_temps.rs");
+        // two literals of the same type on one line get distinct temporaries;
+        // moved temporaries are not dropped by the caller, also when the
+        // callee has no signature in the file
+        assert_eq!(events(&res), vec![
+            "2 GoOutOfScope b",
+            "2 GoOutOfScope a",
+            "3 StaticDie r->None",
+            "3 GoOutOfScope r",
+            "5 PassByMoveTo tmp_rect_5_10->a",
+            "5 PassByMoveTo tmp_rect_5_31->b",
+            "6 PassByStaticReference tmp_rect_6_10->r",
+            "6 GoOutOfScope tmp_rect_6_10",
+            "7 HeapAlloc b",
+            "7 Move tmp_rect_7_21->b",
+            "8 GoOutOfScope b",
+        ]);
+    }
+
+    #[test]
+    fn struct_header() {
+        let mut res = fixture("struct_header.rs");
+        let header = header_gen_str(&mut res);
+        // members in declaration order, nested members after their parent
+        assert!(header.contains("Struct p{y,x};"), "{}", header);
+        assert!(header.contains("Struct l{start,start.y,start.x,end};"), "{}", header);
+        assert!(header.contains("Struct k{};"), "{}", header);
+        assert_eq!(res.diagnostics, vec![
+            (res.files[0].clone(), 8, String::from("field z not found in struct Point")),
+        ]);
+    }
+
+    #[test]
+    fn smart_pointers() {
+        let res = fixture("smart_ptr.rs");
+        // a wrapped literal is moved into the new pointer, only the
+        // pointer goes out of scope
+        assert_eq!(events(&res), vec![
+            "6 HeapAlloc b",
+            "7 HeapAlloc shared",
+            "8 SharedClone shared->other",
+            "9 PassByStaticReference shared->borrow",
+            "9 InteriorBorrow shared->r",
+            "10 PassByStaticReference other->borrow_mut",
+            "10 InteriorMutBorrow other->w",
+            "11 PassByMutableReference w->push",
+            "12 HeapAlloc sq",
+            "12 Move tmp_sq_12_22->sq",
+            "13 HeapAlloc p",
+            "13 Move tmp_point_13_20->p",
+            "14 GoOutOfScope p",
+            "14 GoOutOfScope sq",
+            "14 GoOutOfScope w",
+            "14 GoOutOfScope r",
+            "14 GoOutOfScope other",
+            "14 GoOutOfScope shared",
+            "14 GoOutOfScope b",
+        ]);
+        let pointer = |name: &str| res.rap_info[res.var_alloc[name][0].hash()].pointer.clone();
+        assert_eq!(pointer("b"), vec![PtrKind::Box]);
+        assert_eq!(pointer("other"), vec![PtrKind::Rc, PtrKind::RefCell]);
+        assert_eq!(pointer("w"), vec![PtrKind::RefMut]);
+    }
+
+    #[test]
+    fn returned_references() {
+        let res = fixture("lifetimes.rs");
+        // l borrows from both arguments of longest<'a>()
+        assert_eq!(events(&res), vec![
+            "2 PassByStaticReference x->len",
+            "2 PassByStaticReference y->len",
+            "2 StaticDie x->None",
+            "2 StaticDie y->None",
+            "3 GoOutOfScope y",
+            "3 GoOutOfScope x",
+            "7 PassByStaticReference a->x",
+            "7 PassByStaticReference b->y",
+            "7 StaticBorrow a->l",
+            "7 StaticBorrow b->l",
+            "8 StaticDie l->a",
+            "8 StaticDie l->b",
+            "9 GoOutOfScope l",
+            "9 GoOutOfScope b",
+            "9 GoOutOfScope a",
+        ]);
+        let info = |name: &str| &res.rap_info[res.var_alloc[name][0].hash()];
+        assert_eq!(info("longest()").lifetimes, vec![String::from("'a")]);
+        assert_eq!(info("x").ty, "&'a str");
+        assert_eq!(info("l").lifetimes, vec![String::from("'a")]);
+    }
+
+    #[test]
+    fn generic_parameters() {
+        let res = fixture("generic_params.rs");
+        let rap = |name: &str| res.var_alloc[name][0].clone();
+        assert!(matches!(*rap("d"), ResourceAccessPoint::Owner(_)));
+        assert!(matches!(*rap("items"), ResourceAccessPoint::StaticRef(_)));
+        assert!(matches!(*rap("pair"), ResourceAccessPoint::Owner(_)));
+        assert!(matches!(*rap("grid"), ResourceAccessPoint::Owner(_)));
+        assert!(matches!(*rap("obj"), ResourceAccessPoint::MutRef(_)));
+        // bounds from the generic list and the where clause
+        assert_eq!(res.rap_info[rap("t").hash()].bounds, vec![String::from("Display"), String::from("Clone")]);
+        assert_eq!(events(&res), vec![
+            "2 GoOutOfScope t",
+            "3 StaticDie items->None",
+            "3 MutableDie obj->None",
+            "3 GoOutOfScope obj",
+            "3 GoOutOfScope grid",
+            "3 GoOutOfScope pair",
+            "3 GoOutOfScope items",
+            "3 GoOutOfScope d",
+            "7 PassByMoveTo s->t",
+        ]);
+        // the tuple pattern gets a diagnostic, not a nameless RAP
+        assert_eq!(res.diagnostics, vec![(res.files[0].clone(), 4, String::from("parameter pattern (a , b) of swap() not supported, no RAP created"))]);
+        assert!(res.var_alloc.values().flatten().all(|rap| *rap.hash() != 0));
+    }
+
+    #[test]
+    fn module_associated_functions() {
+        let res = fixture("mod_assoc.rs");
+        // one Function RAP per definition, named like its call key
+        let mut funcs: Vec<&String> = res.var_alloc.keys().filter(|name| name.contains("Rect::")).collect();
+        funcs.sort();
+        assert_eq!(funcs, vec!["shapes::Rect::area()", "shapes::Rect::new()"]);
+        assert_eq!(events(&res), vec![
+            "2 Move shapes::Rect::new()->r",
+            "3 PassByStaticReference r->shapes::Rect::area()",
+            "4 GoOutOfScope a",
+            "4 GoOutOfScope r",
+            "9 Copy w->w",
+            "9 Copy h->h",
+            "9 Move tmp_rect_9_12->shapes::Rect::new()",
+            "10 GoOutOfScope h",
+            "10 GoOutOfScope w",
+            "12 StaticDie self->None",
+            "13 GoOutOfScope self",
+        ]);
+        // -> Rect inside mod shapes is shapes::Rect for the caller too
+        assert_eq!(res.rap_info[res.var_alloc["r"][0].hash()].ty, "shapes::Rect");
+        // calls resolve to the RAPs of the definitions in mod shapes
+        assert_eq!(res.var_alloc["shapes::Rect::new()"][0].name(), "shapes::Rect::new()");
+        assert_eq!(res.call_graph["main()"], vec!["shapes::Rect::new()", "shapes::Rect::area()"]);
+    }
+
+    #[test]
+    fn string_literals() {
+        let mut res = fixture("str_literals.rs");
+        // all literals, even two on one line, borrow the one static_str
+        // resource; x points at the static data of NAME; statics and
+        // consts are never dropped
+        assert_eq!(events(&res), vec![
+            "2 StaticBorrow static_str->a",
+            "2 StaticBorrow static_str->b",
+            "3 StaticBorrow static_str->c",
+            "4 StaticDie a->static_str",
+            "4 StaticDie b->static_str",
+            "4 StaticDie c->static_str",
+            "5 StaticBorrow NAME->x",
+            "6 StaticDie x->NAME",
+            "7 GoOutOfScope x",
+            "7 GoOutOfScope c",
+            "7 GoOutOfScope b",
+            "7 GoOutOfScope a",
+        ]);
+        assert_eq!(res.var_alloc.keys().filter(|name| name.starts_with("static_str")).count(), 1);
+        let static_str = res.var_alloc["static_str"][0].hash();
+        assert!(res.rap_info[static_str].is_static);
+        let header = header_gen_str(&mut res);
+        assert_eq!(header.matches("static_str").count(), 1, "{}", header);
+    }
+
+    #[test]
+    fn index_and_slice() {
+        let res = fixture("index_slice.rs");
+        // &v[0] and &v[i..3] borrow v itself
+        assert_eq!(events(&res), vec![
+            "4 StaticBorrow v->first",
+            "5 StaticBorrow v->part",
+            "6 StaticDie first->v",
+            "6 StaticDie part->v",
+            "8 GoOutOfScope part",
+            "8 GoOutOfScope first",
+            "8 GoOutOfScope i",
+            "8 GoOutOfScope v",
+        ]);
+        // v[i] = 5 writes to v
+        assert_eq!(res.rap_info[res.var_alloc["v"][0].hash()].mutations, vec![7]);
+        assert!(res.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn expression_coverage() {
+        let res = fixture("expr_coverage.rs");
+        // the call inside a match arm is found; break 'outer moves c
+        // out of the loop into v instead of dropping it
+        assert_eq!(events(&res), vec![
+            "1 GoOutOfScope s",
+            "9 PassByMoveTo a->s",
+            "14 Move c->v",
+            "17 GoOutOfScope v",
+            "17 GoOutOfScope r",
+            "17 GoOutOfScope t",
+            "17 GoOutOfScope n",
+            "17 GoOutOfScope b",
+        ]);
+        // c leaves the loop with break 'outer instead of going out of scope there
+        assert!(res.rap_info[res.var_alloc["c"][0].hash()].exits.is_empty());
+    }
+
+    #[test]
+    fn assignments() {
+        let res = fixture("assignments.rs");
+        // s = &y is the first assignment of let s; and borrows y there
+        assert_eq!(events(&res), vec![
+            "10 MutableBorrow x->r",
+            "11 MutableDie r->x",
+            "13 StaticBorrow y->s",
+            "20 StaticDie s->y",
+            "21 GoOutOfScope z",
+            "21 GoOutOfScope s",
+            "21 GoOutOfScope r",
+            "21 GoOutOfScope p",
+            "21 GoOutOfScope y",
+            "21 GoOutOfScope x",
+        ]);
+        let info = |name: &str| &res.rap_info[res.var_alloc[name][0].hash()];
+        // x += 1 and *r = 4 write x, p.x = 5 writes p and its member
+        assert_eq!(info("x").mutations, vec![5, 11]);
+        assert_eq!(info("r").mutations, vec![11]);
+        assert_eq!(info("p").mutations, vec![9]);
+        assert_eq!(res.rap_info[res.var_alloc["p.x"][0].hash()].mutations, vec![9]);
+        // z = 1 / z = 2 initialize z in either branch
+        assert!(info("z").mutations.is_empty());
+        assert_eq!(res.diagnostics, vec![
+            (res.files[0].clone(), 7, String::from("assignment to y which is not declared mutable")),
+        ]);
+    }
+
+    #[test]
+    fn deref_chains() {
+        let res = fixture("deref_chain.rs");
+        let derefs = |name: &str| res.color_info.iter()
+            .filter_map(|stack| stack.get(name))
+            .flatten()
+            .filter(|item| format!("{:?}", item).starts_with("StackItem { SynInfo: Dereference"))
+            .count();
+        // **rr goes through rr and r down to n
+        assert_eq!((derefs("rr"), derefs("r"), derefs("n")), (1, 1, 1));
+        // r stays alive until its use through rr
+        assert_eq!(events(&res), vec![
+            "3 StaticBorrow n->r",
+            "4 StaticBorrow r->rr",
+            "5 StaticDie r->n",
+            "5 StaticDie rr->r",
+            "8 GoOutOfScope ok",
+            "8 GoOutOfScope neg",
+            "8 GoOutOfScope m",
+            "8 GoOutOfScope rr",
+            "8 GoOutOfScope r",
+            "8 GoOutOfScope n",
+        ]);
+        // -n and !true are not dereferences
+        assert_eq!(derefs("neg") + derefs("ok"), 0);
+    }
+
+    #[test]
+    fn reborrows() {
+        let res = fixture("reborrow.rs");
+        // &mut *r1 reborrows s, a &mut is moved and a & copied,
+        // &rt.0 borrows from t through rt; r1 is moved out, not dropped
+        assert_eq!(events(&res), vec![
+            "3 MutableBorrow s->r1",
+            "4 MutableBorrow s->r2",
+            "5 PassByMutableReference r2->push",
+            "5 MutableDie r2->s",
+            "6 Move r1->r3",
+            "7 PassByMutableReference r3->push",
+            "7 MutableDie r3->s",
+            "9 StaticBorrow x->a",
+            "10 Copy a->b",
+            "10 StaticBorrow x->b",
+            "11 StaticBorrow x->c",
+            "12 StaticDie a->x",
+            "12 StaticDie b->x",
+            "12 StaticDie c->x",
+            "14 StaticBorrow t->rt",
+            "15 StaticBorrow t->f",
+            "15 StaticDie rt->t",
+            "16 StaticDie f->t",
+            "17 GoOutOfScope f",
+            "17 GoOutOfScope rt",
+            "17 GoOutOfScope t",
+            "17 GoOutOfScope c",
+            "17 GoOutOfScope b",
+            "17 GoOutOfScope a",
+            "17 GoOutOfScope x",
+            "17 GoOutOfScope r3",
+            "17 GoOutOfScope r2",
+            "17 GoOutOfScope s",
+        ]);
+        let points_to = |name: &str| res.rap_info[res.var_alloc[name][0].hash()].points_to.clone();
+        let s = *res.var_alloc["s"][0].hash();
+        let x = *res.var_alloc["x"][0].hash();
+        let t = *res.var_alloc["t"][0].hash();
+        assert_eq!((points_to("r2"), points_to("r3")), (vec![s], vec![s]));
+        // &&x ultimately points to x
+        assert_eq!(points_to("c"), vec![x]);
+        assert_eq!(points_to("f"), vec![t]);
+    }
+
+    #[test]
+    fn inferred_let_types() {
+        let res = fixture("type_infer.rs");
+        let rap = |name: &str| res.var_alloc[name][0].clone();
+        let ty = |name: &str| res.rap_info[rap(name).hash()].ty.clone();
+        // std signatures and functions of the file, no annotations
+        assert_eq!(ty("first"), "Option<&_>");
+        assert_eq!(ty("s"), "&i32");
+        assert_eq!(ty("it"), "Iter<_>");
+        assert_eq!(ty("len"), "usize");
+        // borrowing results are references to v, a usize is not
+        for name in ["first", "s", "it"].iter() {
+            assert!(matches!(*rap(name), ResourceAccessPoint::StaticRef(_)), "{}", name);
         }
+        assert!(matches!(*rap("len"), ResourceAccessPoint::Owner(_)));
+        assert_eq!(res.rap_info[rap("it").hash()].points_to, vec![*rap("v").hash()]);
+    }
+
+    #[test]
+    fn call_resolution() {
+        let res = fixture("call_graph.rs");
+        assert_eq!(res.call_graph.get("main()"), Some(&vec![String::from("Counter::new()"), String::from("helper()")]));
+        // Self::start() inside impl Counter
+        assert_eq!(res.call_graph.get("Counter::new()"), Some(&vec![String::from("Counter::start()")]));
+        // the definition and both calls of helper() share one RAP
+        let helper = format!("{:?}", res.var_alloc["helper()"][0]);
+        let uses: Vec<String> = res.color_info.iter()
+            .filter_map(|stack| stack.get("helper()"))
+            .flatten()
+            .map(|item| format!("{:?}", item))
+            .collect();
+        assert_eq!(uses.len(), 3);
+        assert!(uses.iter().all(|item| item.contains(&format!("ItemOrig: {}", helper))), "{:#?}", uses);
+        assert_eq!(events(&res), vec![
+            "7 Move tmp_counter_7_8->Counter::start()",
+            "12 Move Counter::new()->c",
+            "15 GoOutOfScope c",
+        ]);
+    }
+
+    #[test]
+    fn constructor_calls() {
+        let res = fixture("constructors.rs");
+        // the returned literal moves to the function, then from it to the let
+        assert_eq!(events(&res), vec![
+            "2 Move Rect::new()->r",
+            "3 Move Rect::square()->s",
+            "5 GoOutOfScope s",
+            "5 GoOutOfScope r",
+            "14 Copy w->w",
+            "14 Copy h->h",
+            "14 Move tmp_rect_14_8->Rect::new()",
+            "15 GoOutOfScope h",
+            "15 GoOutOfScope w",
+            "19 Move tmp_rect_19_19->Rect::square()",
+            "21 Copy side->w",
+            "21 Copy side->h",
+            "21 Move tmp_rect_21_8->Rect::square()",
+            "22 GoOutOfScope side",
+        ]);
+        // Rect is declared after main but still gives r its members
+        assert!(matches!(&*res.var_alloc["r"][0], ResourceAccessPoint::Struct(_)));
+        assert!(res.var_alloc.contains_key("r.w") && res.var_alloc.contains_key("r.h"));
+    }
+
+    #[test]
+    fn async_functions() {
+        let res = fixture("async_fn.rs");
+        // an async move block copies or moves what it uses, macros included
+        assert_eq!(events(&res), vec![
+            "2 PassByStaticReference s->len",
+            "2 StaticDie s->None",
+            "3 GoOutOfScope s",
+            "7 PassByStaticReference name->s",
+            "10 Copy count->task",
+            "10 Move greeting->task",
+            "13 GoOutOfScope inner",
+            "16 GoOutOfScope task",
+            "16 GoOutOfScope count",
+            "16 GoOutOfScope n",
+            "16 GoOutOfScope name",
+            "20 GoOutOfScope fut",
+        ]);
+        let info = |name: &str| &res.rap_info[res.var_alloc[name][0].hash()];
+        assert_eq!(info("task").captures, vec![*res.var_alloc["count"][0].hash(), *res.var_alloc["greeting"][0].hash()]);
+        // variables declared before an .await stay alive across it
+        assert_eq!(info("name").awaits, vec![7, 14]);
+        assert_eq!(info("n").awaits, vec![14]);
+        assert!(info("greeting").awaits.is_empty());
+        assert_eq!(info("fut").ty, "impl Future<Output = ()>");
+        assert_eq!(res.call_graph.get("run()"), Some(&vec![String::from("load()")]));
+    }
+
+    #[test]
+    fn try_operator() {
+        let res = fixture("try_ops.rs");
+        // the call result is held by a temporary that ? either unwraps into
+        // the let or returns to the enclosing function, main() included
+        assert_eq!(events(&res), vec![
+            "5 EarlyReturn tmp_result_5_16->read_file()",
+            "5 Move tmp_result_5_16->f",
+            "5 StaticDie path->None",
+            "7 EarlyReturn tmp_result_7_4->read_file()",
+            "7 GoOutOfScope tmp_result_7_4",
+            "9 GoOutOfScope text",
+            "9 GoOutOfScope f",
+            "9 GoOutOfScope path",
+            "12 EarlyReturn v->first()",
+            "12 Move v->items",
+            "14 GoOutOfScope items",
+            "18 PassByStaticReference path->path",
+            "18 EarlyReturn tmp_result_18_15->main()",
+            "18 Move tmp_result_18_15->text",
+            "21 GoOutOfScope text",
+            "21 GoOutOfScope path",
+            // a Copy operand is copied and still dropped at its scope end
+            "24 EarlyReturn v->second()",
+            "24 Copy v->n",
+            "26 GoOutOfScope n",
+            "26 GoOutOfScope v",
+        ]);
+        assert!(matches!(&*res.var_alloc["main()"][0], ResourceAccessPoint::Function(_)));
+        let info = |name: &str| &res.rap_info[res.var_alloc[name][0].hash()];
+        assert_eq!(info("tmp_result_18_15").ty, "io::Result<String>");
+        // the binding gets the Ok / Some payload
+        assert_eq!(info("items").ty, "Vec<i32>");
+        assert_eq!(res.rap_info[res.var_alloc["text"].last().unwrap().hash()].ty, "String");
+    }
+
+    #[test]
+    fn unsafe_and_labels() {
+        let res = fixture("unsafe_labels.rs");
+        // raw pointers point to x without borrowing it
+        assert_eq!(events(&res), vec![
+            "5 RawPointer x->p",
+            "6 RawPointer x->q",
+            "11 GoOutOfScope inner",
+            "16 PassByStaticReference t->len",
+            "20 GoOutOfScope t",
+            "21 GoOutOfScope s",
+            "24 PassByStaticReference w->is_empty",
+            "28 GoOutOfScope w",
+            "35 GoOutOfScope u",
+            "35 GoOutOfScope i",
+            "37 GoOutOfScope found",
+            "37 GoOutOfScope total",
+            "37 GoOutOfScope q",
+            "37 GoOutOfScope p",
+            "37 GoOutOfScope x",
+        ]);
+        let info = |name: &str| &res.rap_info[res.var_alloc[name][0].hash()];
+        let x = *res.var_alloc["x"][0].hash();
+        assert_eq!(info("p").pointer, vec![PtrKind::RawConst]);
+        assert_eq!(info("q").pointer, vec![PtrKind::RawMut]);
+        assert_eq!(info("p").points_to, vec![x]);
+        // *q += *p inside unsafe writes through q to x
+        assert_eq!(info("q").mutations, vec![9]);
+        assert_eq!(info("x").mutations, vec![9]);
+        // break 'outer and continue 'outer leave both loop bodies early,
+        // break 'search leaves the labelled block, continue the for body
+        assert_eq!(info("s").exits, vec![17, 19]);
+        assert_eq!(info("t").exits, vec![17, 19]);
+        assert_eq!(info("w").exits, vec![25]);
+        assert_eq!(info("u").exits, vec![32]);
+        assert_eq!(info("i").exits, vec![32]);
+        assert!(info("inner").exits.is_empty());
     }
-}
\ No newline at end of file
+}
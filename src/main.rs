@@ -29,10 +29,10 @@ fn main() {
   println!("{:?}", sourcefname);
   let parse_res = syn_parse(&sourcefname);
   match parse_res {
-    Ok((rap, color_info)) => {
-      // println!("{}", header_gen_str(&rap));
-      println!("{:?}", rap);
-      println!("{:?}", color_info);
+    Ok(res) => {
+      // println!("{}", header_gen_str(&mut res));
+      println!("{:?}", res.var_alloc);
+      println!("{:?}", res.color_info);
       // let res = asource_gen(&file_name, &color_info,);
     }
     Err(e) => println!("error parsing header: {:?}", e),
@@ -63,7 +63,7 @@ fn main() {
 //   let parse_res = syn_parse::syn_parse(&file_name);
 //   match parse_res {
 //     Ok(v) => {
-//       let header = syn_parse::header_gen_str(&v.0);
+//       let header = syn_parse::header_gen_str(&mut v);
 //       let origin_contents = fs::read_to_string(&file_name);
 //       file_name.pop();
 //       file_name.push("main.rs");
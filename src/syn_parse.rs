@@ -1,11 +1,11 @@
 #![feature(get_mut_unchecked)]
 use syn::{Stmt, Expr, Pat, Item, FnArg, Type};
 use log::{debug, info};
-use std::collections::{HashMap, BTreeMap};
+use std::collections::{HashMap, HashSet, BTreeMap};
 use std::error::Error;
 use std::fs::File;
 use std::io::{Read, BufReader, BufRead};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use syn::spanned::Spanned;
 use std::sync::Arc;
 use rustviz_lib::data::{ResourceAccessPoint, 
@@ -14,7 +14,9 @@ use rustviz_lib::data::{ResourceAccessPoint,
     MutRef,
     StaticRef,
     Function};
-use proc_macro2::{Ident, Span};
+use proc_macro2::Ident;
+use quote::ToTokens;
+use syn::visit::Visit;
 
 struct data_pkg {
     ///
@@ -37,9 +39,399 @@ struct data_pkg {
     /// # var_def
     /// 
     /// a map that contain 
+    /// 
+    /// # events
+    /// 
+    /// ownership events discovered while parsing, tagged with 
+    /// the source file and line they happen on
+    /// 
+    /// Vec<(file, line, EventItem)>
+    /// 
+    /// # fn_sigs
+    /// 
+    /// function signatures collected before parsing the bodies, 
+    /// so that a call site can be matched against its callee 
+    /// no matter where the callee is defined
+    /// 
+    /// Map<func_name(), FnSig>
+    /// 
+    /// # method_recv
+    /// 
+    /// receiver kind of the methods declared in local impl blocks
+    /// 
+    /// Map<Type::method, RecvKind>
+    /// 
+    /// # rap_info
+    /// 
+    /// Map<RAP hash, RapInfo>
+    /// 
+    /// # last_use
+    /// 
+    /// the last line each RAP is used on, keyed by RAP hash
+    /// 
+    /// # ref_targets
+    /// 
+    /// owners a reference borrows from, keyed by reference hash
+    /// 
+    /// # fn_refs
+    /// 
+    /// references declared in the function currently being parsed,
+    /// they die at their last use (NLL) once the body is done
+    /// 
+    /// # scopes
+    /// 
+    /// stack of the scopes (function bodies and blocks) enclosing the
+    /// code being parsed, see ScopeFrame
+    /// 
+    /// # moved
+    /// 
+    /// hashes of the RAPs whose resource has been moved out
+    /// 
+    /// # mod_path
+    /// 
+    /// module path of the items being parsed, [] for the input file,
+    /// definitions are registered as mod_a::mod_b::name
+    /// 
+    /// # mod_files
+    /// 
+    /// source file of every out-of-line module
+    /// 
+    /// Map<mod_a::mod_b, file>
+    /// 
+    /// # use_alias
+    /// 
+    /// names brought in by `use`, keyed by the module qualified alias
+    /// 
+    /// Map<mod_a::alias, full::path>
+    /// 
+    /// # cur_file
+    /// 
+    /// source file of the items being parsed
+    /// 
+    /// # diagnostics
+    /// 
+    /// (file, line, message) for code the parser could not model
+    /// 
+    /// # struct_info
+    /// 
+    /// shape of every struct definition, see StructDef
+    /// 
+    /// Map<struct_type, StructDef>
+    /// 
+    /// # members
+    /// 
+    /// member RAPs of struct instances
+    /// 
+    /// Map<owner.field, Arc<ResourceAccessPoint>>
+    /// 
+    /// # stmt_temps
+    /// 
+    /// anonymous owners (struct literals not bound by a let) created by
+    /// the statement being parsed, dropped at the end of the statement
+    /// 
+    /// # traits
+    /// 
+    /// Map<trait name, method names>
+    /// 
+    /// # trait_impls
+    /// 
+    /// Map<self type, traits implemented for it>
+    /// 
+    /// # cur_self
+    /// 
+    /// self type of the impl block being parsed
+    /// 
+    /// # loop_exits
+    /// 
+    /// loops and labelled blocks enclosing the code being parsed,
+    /// the targets of break and continue
+    /// 
+    /// # globals
+    /// 
+    /// module qualified names of consts and statics
+    /// 
+    /// # uninit
+    /// 
+    /// let bindings declared without a value (let x;), by name: their
+    /// first assignment initializes them, see PendingLocal
+    /// 
+    /// # late_init
+    /// 
+    /// hashes of deferred bindings first assigned in a nested block:
+    /// if c { x = 1; } else { x = 2; } initializes x in either branch
+    /// 
+    /// # cur_fn
+    /// 
+    /// name of the Function RAP being parsed
+    /// 
+    /// # call_graph
+    /// 
+    /// Map<caller, callees defined in the file>
+    /// 
+    /// # async_scope
+    /// 
+    /// index in scopes of the outermost frame of the async fn body or
+    /// async block being parsed, None outside async code
     color_info: Vec<HashMap<String, Vec<StackItem>>>,
     var_alloc: HashMap<String, Vec<Arc<ResourceAccessPoint>>>,
-    var_def: HashMap<String, HashMap<String, Arc<ResourceAccessPoint>>> // struct only
+    var_def: HashMap<String, HashMap<String, Arc<ResourceAccessPoint>>>, // struct only
+    events: Vec<(PathBuf, usize, EventItem)>,
+    fn_sigs: HashMap<String, FnSig>,
+    method_recv: HashMap<String, RecvKind>,
+    rap_info: HashMap<u64, RapInfo>,
+    last_use: HashMap<u64, usize>,
+    ref_targets: HashMap<u64, Vec<Arc<ResourceAccessPoint>>>,
+    fn_refs: Vec<Arc<ResourceAccessPoint>>,
+    scopes: Vec<ScopeFrame>,
+    moved: HashSet<u64>,
+    mod_path: Vec<String>,
+    mod_files: HashMap<String, PathBuf>,
+    use_alias: HashMap<String, String>,
+    cur_file: PathBuf,
+    diagnostics: Vec<(PathBuf, usize, String)>,
+    struct_info: HashMap<String, StructDef>,
+    members: HashMap<String, Arc<ResourceAccessPoint>>,
+    stmt_temps: Vec<Arc<ResourceAccessPoint>>,
+    traits: HashMap<String, Vec<String>>,
+    trait_impls: HashMap<String, Vec<String>>,
+    cur_self: Option<String>,
+    loop_exits: Vec<LoopExit>,
+    globals: HashSet<String>,
+    uninit: HashMap<String, PendingLocal>,
+    late_init: HashSet<u64>,
+    cur_fn: Option<String>,
+    call_graph: HashMap<String, Vec<String>>,
+    async_scope: Option<usize>,
+}
+
+#[derive(Debug)]
+struct ScopeFrame {
+    ///
+    /// a function body or block: the line of its closing brace,
+    /// the owners/references declared in it, in declaration order,
+    /// and whether it is a function body, whose tail expression is
+    /// returned to the caller
+    /// 
+    end_line: usize,
+    decls: Vec<Arc<ResourceAccessPoint>>,
+    is_fn: bool,
+}
+
+#[derive(Debug)]
+struct LoopExit {
+    ///
+    /// a loop or labelled block: its label ('outer: loop { .. }), whether
+    /// it is a loop, which unlabelled break and continue leave, the scope
+    /// frame of its body, whether a let binds its value and the binds of
+    /// the values break carries out to that let
+    /// 
+    label: Option<String>,
+    is_loop: bool,
+    scope: usize,
+    bound: bool,
+    binds: Vec<PendingBind>,
+}
+
+#[derive(Debug)]
+struct PendingLocal {
+    ///
+    /// a let binding without a value: its pattern and type as written,
+    /// and where it was declared (stack, scope frame, position in it)
+    /// 
+    pat: syn::PatIdent,
+    derive: expr_derive,
+    stack_num: usize,
+    scope: usize,
+    pos: usize,
+}
+
+#[derive(Debug, Clone)]
+struct FnSig {
+    ///
+    /// RAPs allocated for a function definition: the function
+    /// itself and its parameters (with their written type) in
+    /// declaration order (position in the signature, ident, RAP, type),
+    /// the trait bounds of generic parameters by argument name, its
+    /// lifetime parameters and, if it returns a reference,
+    /// (is mut, lifetime) of that reference, the written return type
+    /// and whether it is an async fn
+    /// 
+    func: ResourceAccessPoint,
+    args: Vec<(usize, Ident, ResourceAccessPoint, String)>,
+    arg_bounds: HashMap<String, Vec<String>>,
+    lifetimes: Vec<String>,
+    ret_ref: Option<(bool, Option<String>)>,
+    ret_ty: String,
+    is_async: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum RecvKind {
+    ///
+    /// how a method takes its receiver
+    /// 
+    Borrow, // &self
+    MutBorrow, // &mut self
+    Move, // self
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StructKind {
+    Named, // struct Point { x: i32, y: i32 }
+    Tuple, // struct Meters(f64);
+    Unit, // struct Marker;
+}
+
+#[derive(Debug, Clone)]
+pub struct StructDef {
+    ///
+    /// a struct definition as written in the source
+    /// 
+    /// # generics
+    /// 
+    /// lifetime, type and const parameters: ["'a", "T", "N"]
+    /// 
+    /// # fields
+    /// 
+    /// (field name, field type) in declaration order,
+    /// tuple struct fields are named by position: "0", "1"
+    /// 
+    pub kind: StructKind,
+    pub generics: Vec<String>,
+    pub fields: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PtrKind {
+    ///
+    /// smart pointers, the guards handed out by RefCell and raw pointers
+    /// 
+    Box,
+    Rc,
+    Arc,
+    RefCell,
+    Cell,
+    Ref, // guard of RefCell::borrow()
+    RefMut, // guard of RefCell::borrow_mut()
+    RawConst, // *const T
+    RawMut, // *mut T
+}
+
+#[derive(Debug)]
+pub struct ParseResult {
+    ///
+    /// everything syn_parse() found in the source file
+    /// 
+    /// # var_alloc
+    /// 
+    /// Map<var_name, Vec<Arc<ResourceAccessPoint>>>
+    /// 
+    /// # color_info
+    /// 
+    /// Vec(stack)<Map<ident_name, Vec(list of)<StackItem>>>
+    /// 
+    /// # events
+    /// 
+    /// Vec<(file, line, EventItem)>, sorted by file (in the order
+    /// of files) and then by line
+    /// 
+    /// # rap_info
+    /// 
+    /// Map<RAP hash, RapInfo>
+    /// 
+    /// # files
+    /// 
+    /// every source file parsed, the input file first followed by 
+    /// the files of out-of-line modules
+    /// 
+    /// # structs
+    /// 
+    /// Map<struct_type, StructDef>
+    /// 
+    /// # diagnostics
+    /// 
+    /// (file, line, message) for code the parser could not model
+    /// 
+    /// # call_graph
+    /// 
+    /// Map<caller, callees>, both named like their Function RAPs:
+    /// main() -> [shapes::Rect::new(), shapes::Rect::area()]
+    /// 
+    pub var_alloc: HashMap<String, Vec<Arc<ResourceAccessPoint>>>,
+    pub color_info: Vec<HashMap<String, Vec<StackItem>>>,
+    pub events: Vec<(PathBuf, usize, EventItem)>,
+    pub rap_info: HashMap<u64, RapInfo>,
+    pub files: Vec<PathBuf>,
+    pub structs: HashMap<String, StructDef>,
+    pub diagnostics: Vec<(PathBuf, usize, String)>,
+    pub call_graph: HashMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RapInfo {
+    ///
+    /// what we know about a RAP beyond ResourceAccessPoint,
+    /// keyed by RAP hash in ParseResult::rap_info
+    /// 
+    /// # ty
+    /// 
+    /// type of the RAP when it is known: &Point, Rect, ...
+    /// empty if unknown
+    /// 
+    /// # pointer
+    /// 
+    /// smart pointer layers, outermost first:
+    /// Rc<RefCell<T>> -> [Rc, RefCell]
+    /// 
+    /// # lifetimes
+    /// 
+    /// lifetime parameters of a function, or the lifetime of a
+    /// reference argument / returned reference: ['a]
+    /// 
+    /// # bounds
+    /// 
+    /// trait bounds of a generic or impl Trait argument:
+    /// fn show<T: Display>(t: T) where T: Clone -> [Display, Clone]
+    /// 
+    /// # is_static
+    /// 
+    /// const, static or string literal data: lives for the whole
+    /// program and never goes out of scope
+    /// 
+    /// # points_to
+    /// 
+    /// hashes of the owners a reference ultimately points to,
+    /// through reborrows and references to references
+    /// 
+    /// # mutations
+    /// 
+    /// lines the RAP is written on or through:
+    /// x = 1; x += 1; p.x = 1; v[i] = x; *r = 2;
+    /// 
+    /// # captures
+    /// 
+    /// hashes of the variables a closure or async block bound to
+    /// this RAP captures from its environment
+    /// 
+    /// # awaits
+    /// 
+    /// lines of the .await points the RAP is alive across
+    /// 
+    /// # exits
+    /// 
+    /// lines of the break / continue that leave the RAP's scope
+    /// before its closing brace
+    /// 
+    pub ty: String,
+    pub pointer: Vec<PtrKind>,
+    pub lifetimes: Vec<String>,
+    pub bounds: Vec<String>,
+    pub is_static: bool,
+    pub points_to: Vec<u64>,
+    pub mutations: Vec<usize>,
+    pub captures: Vec<u64>,
+    pub awaits: Vec<usize>,
+    pub exits: Vec<usize>,
 }
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone)]
@@ -54,6 +446,7 @@ pub struct StackItem {
     /// 
     SynInfo: Infoitem,
     ItemOrig: Arc<ResourceAccessPoint>,
+    SrcFile: PathBuf, // source file the SynInfo span belongs to
 }
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone)]
@@ -70,69 +463,405 @@ pub enum Infoitem {
     Reference(syn::ExprReference), // &a;
     Dereference(syn::ExprUnary), //*a;
     ExprStruct(syn::Ident), // struct literal expression
+    TupleArg(syn::Expr), // positional field in tuple struct literal: Meters(3.0)
+    Global(syn::Ident), // const MAX: i32 = 5; static mut COUNT: i32 = 0;
+    StrLit(syn::LitStr), // let s = "hi";
+    Try(syn::ExprTry), // File::open(p)?
     Macro(syn::PathSegment),
     Path(syn::ExprPath)
 }
 
-fn path_fmt(exprpath : &syn::ExprPath) -> String {
-    /// 
-    /// reconstruct ExprPath item to its original string
+#[derive(Debug, Clone)]
+pub enum EventItem {
+    ///
+    /// ownership events between RAPs, following the naming of
+    /// RustViz's ExternalEvent
     /// 
+    PassByMoveTo { from: Option<Arc<ResourceAccessPoint>>, to: Option<Arc<ResourceAccessPoint>> }, // take(s)
+    PassByStaticReference { from: Option<Arc<ResourceAccessPoint>>, to: Option<Arc<ResourceAccessPoint>> }, // borrow(&s)
+    PassByMutableReference { from: Option<Arc<ResourceAccessPoint>>, to: Option<Arc<ResourceAccessPoint>> }, // change(&mut s)
+    Copy { from: Option<Arc<ResourceAccessPoint>>, to: Option<Arc<ResourceAccessPoint>> }, // takes(n) with n: i32
+    StaticBorrow { from: Option<Arc<ResourceAccessPoint>>, to: Option<Arc<ResourceAccessPoint>> }, // let r = &s;
+    MutableBorrow { from: Option<Arc<ResourceAccessPoint>>, to: Option<Arc<ResourceAccessPoint>> }, // let r = &mut s;
+    StaticDie { from: Option<Arc<ResourceAccessPoint>>, to: Option<Arc<ResourceAccessPoint>> }, // last use of r = &s
+    MutableDie { from: Option<Arc<ResourceAccessPoint>>, to: Option<Arc<ResourceAccessPoint>> }, // last use of r = &mut s
+    GoOutOfScope { ro: Arc<ResourceAccessPoint> }, // closing brace of the declaring scope
+    Move { from: Option<Arc<ResourceAccessPoint>>, to: Option<Arc<ResourceAccessPoint>> }, // let b = a;
+    HeapAlloc { ro: Arc<ResourceAccessPoint> }, // let b = Box::new(5);
+    SharedClone { from: Option<Arc<ResourceAccessPoint>>, to: Option<Arc<ResourceAccessPoint>> }, // let b = Rc::clone(&a);
+    InteriorBorrow { from: Option<Arc<ResourceAccessPoint>>, to: Option<Arc<ResourceAccessPoint>> }, // let r = c.borrow();
+    InteriorMutBorrow { from: Option<Arc<ResourceAccessPoint>>, to: Option<Arc<ResourceAccessPoint>> }, // let m = c.borrow_mut();
+    EarlyReturn { from: Option<Arc<ResourceAccessPoint>>, to: Option<Arc<ResourceAccessPoint>> }, // res?; may return the error to the caller
+    RawPointer { from: Option<Arc<ResourceAccessPoint>>, to: Option<Arc<ResourceAccessPoint>> }, // let p = &x as *const i32;
+}
+
+/// reconstruct ExprPath item to its original string
+fn path_fmt(exprpath : &syn::ExprPath) -> String {
+    syn_path_fmt(&exprpath.path)
+}
+
+fn syn_path_fmt(path : &syn::Path) -> String {
+    // same as path_fmt() for a bare syn::Path
     let mut pathname = "".to_owned();
-    for seg in exprpath.path.segments.iter() {
+    for seg in path.segments.iter() {
         pathname.push_str(&seg.ident.to_string());
         pathname.push_str(&String::from("::"));
     }
     pathname[0..pathname.len()-2].to_string()
 }
 
-pub fn syn_parse(FileName : &PathBuf) 
-    -> Result<(HashMap<String, Vec<Arc<ResourceAccessPoint>>>, Vec<HashMap<String, Vec<StackItem>>>), Box<Error>> {    
-    ///
-    /// initiate parameters for parse_item() call
-    /// 
-    /// # FileName
-    /// 
-    /// PathBuf::from(source_file_destination)
-    /// 
-    /// # return
-    /// 
-    /// Result<(var_alloc, color_info), ERROR)
-    /// 
+fn type_fmt(ty: &syn::Type) -> String {
+    // reconstruct a type to its written form: &'a mut Vec<T>
+    match ty {
+        Type::Path(type_path) => {
+            let mut segs = Vec::new();
+            for seg in type_path.path.segments.iter() {
+                match &seg.arguments {
+                    syn::PathArguments::AngleBracketed(angle) => {
+                        let args: Vec<String> = angle.args.iter().map(|arg| {
+                            match arg {
+                                syn::GenericArgument::Type(ty) => type_fmt(ty),
+                                syn::GenericArgument::Lifetime(lifetime) => format!("{}", lifetime),
+                                syn::GenericArgument::Binding(binding) => format!("{} = {}", binding.ident, type_fmt(&binding.ty)),
+                                _ => arg.to_token_stream().to_string(),
+                            }
+                        }).collect();
+                        segs.push(format!("{}<{}>", seg.ident, args.join(", ")));
+                    },
+                    _ => segs.push(format!("{}", seg.ident)),
+                }
+            }
+            segs.join("::")
+        },
+        Type::Reference(type_reference) => {
+            let mut ref_str = String::from("&");
+            if let Some(lifetime) = &type_reference.lifetime {
+                ref_str.push_str(&format!("{} ", lifetime));
+            }
+            if let Some(_mutability) = &type_reference.mutability {
+                ref_str.push_str("mut ");
+            }
+            ref_str.push_str(&type_fmt(&type_reference.elem));
+            ref_str
+        },
+        Type::Ptr(type_ptr) => {
+            if let Some(_mutability) = &type_ptr.mutability {
+                format!("*mut {}", type_fmt(&type_ptr.elem))
+            } else {
+                format!("*const {}", type_fmt(&type_ptr.elem))
+            }
+        },
+        Type::Slice(type_slice) => format!("[{}]", type_fmt(&type_slice.elem)),
+        Type::Array(type_array) => format!("[{}; {}]", type_fmt(&type_array.elem), type_array.len.to_token_stream()),
+        Type::Tuple(type_tuple) => {
+            let elems: Vec<String> = type_tuple.elems.iter().map(type_fmt).collect();
+            format!("({})", elems.join(", "))
+        },
+        Type::Paren(type_paren) => type_fmt(&type_paren.elem),
+        Type::Group(type_group) => type_fmt(&type_group.elem),
+        Type::ImplTrait(type_impl) => format!("impl {}", bound_fmt(&type_impl.bounds)),
+        Type::TraitObject(type_trait) => format!("dyn {}", bound_fmt(&type_trait.bounds)),
+        Type::Never(_) => String::from("!"),
+        _ => ty.to_token_stream().to_string(),
+    }
+}
+
+fn bound_fmt(bounds: &syn::punctuated::Punctuated<syn::TypeParamBound, syn::Token![+]>) -> String {
+    // Display + 'a
+    let bounds: Vec<String> = bounds.iter().map(|bound| {
+        match bound {
+            syn::TypeParamBound::Trait(trait_bound) => syn_path_fmt(&trait_bound.path),
+            syn::TypeParamBound::Lifetime(lifetime) => format!("{}", lifetime),
+        }
+    }).collect();
+    bounds.join(" + ")
+}
+
+fn generics_fmt(generics: &syn::Generics) -> Vec<String> {
+    // names of the generic parameters: <'a, T, const N: usize> -> ['a, T, N]
+    generics.params.iter().map(|param| {
+        match param {
+            syn::GenericParam::Lifetime(lifetime_def) => format!("{}", lifetime_def.lifetime),
+            syn::GenericParam::Type(type_param) => format!("{}", type_param.ident),
+            syn::GenericParam::Const(const_param) => format!("{}", const_param.ident),
+        }
+    }).collect()
+}
+
+/// initiate parameters for parse_item() call
+///
+/// # FileName
+///
+/// PathBuf::from(source_file_destination)
+///
+/// # return
+///
+/// Result<ParseResult, ERROR)
+pub fn syn_parse(FileName : &PathBuf) -> Result<ParseResult, Box<dyn Error>> {    
     let mut file = File::open(FileName)?;
     let mut content = String::new();
     file.read_to_string(&mut content)?;
-    let ast = syn::parse_file(&content)?;
+    let mut ast = syn::parse_file(&content)?;
     debug!("{:#?}", ast);
+    // pull out-of-line modules into the tree
+    let mut mod_files = HashMap::new();
+    let mod_dir = match FileName.parent() {
+        Some(dir) => dir.to_path_buf(),
+        None => PathBuf::new(),
+    };
+    let mut diagnostics = Vec::new();
+    mod_expand(&mut ast.items, FileName, "", &mod_dir, &mut mod_files, &mut diagnostics)?;
     let mut data_pkg = data_pkg {
         color_info: Vec::new(),
         var_alloc: HashMap::new(),
         var_def: HashMap::new(),
+        events: Vec::new(),
+        fn_sigs: HashMap::new(),
+        method_recv: HashMap::new(),
+        rap_info: HashMap::new(),
+        last_use: HashMap::new(),
+        ref_targets: HashMap::new(),
+        fn_refs: Vec::new(),
+        scopes: Vec::new(),
+        moved: HashSet::new(),
+        mod_path: Vec::new(),
+        mod_files,
+        use_alias: HashMap::new(),
+        cur_file: FileName.clone(),
+        diagnostics,
+        struct_info: HashMap::new(),
+        members: HashMap::new(),
+        stmt_temps: Vec::new(),
+        traits: HashMap::new(),
+        trait_impls: HashMap::new(),
+        cur_self: None,
+        loop_exits: Vec::new(),
+        globals: HashSet::new(),
+        uninit: HashMap::new(),
+        late_init: HashSet::new(),
+        cur_fn: None,
+        call_graph: HashMap::new(),
+        async_scope: None,
     };
     data_pkg.color_info.push(HashMap::new());
     let mut hash_num: u64 = 1;
+    use_alias_collect(&ast.items, &mut data_pkg);
+    struct_collect(&ast.items, &mut data_pkg, &mut hash_num);
+    trait_collect(&ast.items, &mut data_pkg);
+    global_collect(&ast.items, &mut data_pkg, &mut hash_num);
+    fn_sig_collect(&ast.items, &mut data_pkg, &mut hash_num);
     parse_item(&ast.items, &mut data_pkg, &mut hash_num, 0);
     // color_gen(&color_info);
-    Ok((data_pkg.var_alloc, data_pkg.color_info))
+    let mut files = vec![FileName.clone()];
+    let mut mod_files: Vec<(String, PathBuf)> = data_pkg.mod_files.into_iter().collect();
+    mod_files.sort();
+    files.extend(mod_files.into_iter().map(|(_, file)| file));
+    data_pkg.events.sort_by_key(|(file, line, _)| (files.iter().position(|f| f == file), *line));
+    Ok(ParseResult {
+        var_alloc: data_pkg.var_alloc,
+        color_info: data_pkg.color_info,
+        events: data_pkg.events,
+        rap_info: data_pkg.rap_info,
+        files,
+        diagnostics: data_pkg.diagnostics,
+        structs: data_pkg.struct_info,
+        call_graph: data_pkg.call_graph,
+    })
 }
 
-pub fn asource_gen(FileName : &PathBuf, color_info: &Vec<HashMap<String, Vec<StackItem>>>, var_map: &HashMap<String, ResourceAccessPoint>) -> Result<String, Box<Error>>{
-    ///
-    /// Generate annotated source and write into file
-    /// 
-    /// # FileName
-    /// 
-    /// PathBuf::from(source_file_destination)
-    /// 
-    /// # color_info
-    /// 
-    /// color_info 
-    /// 
+/// load `mod shapes;` declarations from shapes.rs or shapes/mod.rs
+/// next to the declaring module and turn them into inline modules,
+/// recording which file each module came from
+///
+/// # mod_file
+///
+/// source file items were read from
+///
+/// # mod_name
+///
+/// module path of items, "" for the input file
+///
+/// # mod_dir
+///
+/// directory that holds the files of the submodules of items
+fn mod_expand(items: &mut [syn::Item],
+    mod_file: &Path,
+    mod_name: &str,
+    mod_dir: &Path,
+    mod_files: &mut HashMap<String, PathBuf>,
+    diagnostics: &mut Vec<(PathBuf, usize, String)>) -> Result<(), Box<dyn Error>> {
+    for item in items.iter_mut() {
+        if let Item::Mod(itemmod) = item {
+            let mut child_file = mod_file.to_path_buf();
+            let child_name = mod_name_fmt(mod_name, &format!("{}", itemmod.ident));
+            let child_dir = mod_dir.join(format!("{}", itemmod.ident));
+            if itemmod.content.is_none() {
+                let candidates = vec![
+                    mod_dir.join(format!("{}.rs", itemmod.ident)),
+                    child_dir.join("mod.rs"),
+                ];
+                match candidates.into_iter().find(|file| file.is_file()) {
+                    Some(found) => {
+                        debug!("mod {} found in {:?}", child_name, found);
+                        let mut content = String::new();
+                        File::open(&found)?.read_to_string(&mut content)?;
+                        let mod_ast = syn::parse_file(&content)?;
+                        itemmod.content = Some((syn::token::Brace::default(), mod_ast.items));
+                        child_file = found.clone();
+                        mod_files.insert(child_name.clone(), found);
+                    },
+                    None => {
+                        diag_insert(mod_file, itemmod.span().start().line, format!("file for mod {} not found", child_name), diagnostics);
+                        continue
+                    }
+                }
+            }
+            if let Some((_, mod_items)) = &mut itemmod.content {
+                mod_expand(mod_items, &child_file, &child_name, &child_dir, mod_files, diagnostics)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn mod_name_fmt(mod_name: &str, name: &str) -> String {
+    // qualify a name with its module path: shapes + Rect -> shapes::Rect
+    if mod_name.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}::{}", mod_name, name)
+    }
+}
+
+fn mod_resolve<F: Fn(&str) -> bool>(name: &str,
+    data: &data_pkg,
+    exists: F) -> String {
+    // resolve a path written in the current module to the module
+    // qualified name a definition was registered under:
+    // crate:: / self:: / super:: prefixes, then the current module and
+    // its ancestors, then `use` aliases; unresolved names are returned as is
+    let mut mod_path = data.mod_path.clone();
+    let mut rest: Vec<String> = name.split("::").map(|seg| seg.to_string()).collect();
+    let mut anchored = false;
+    loop {
+        match rest.first().map(|seg| seg.as_str()) {
+            Some("crate") => {
+                mod_path.clear();
+                anchored = true;
+            },
+            Some("self") if rest.len() > 1 => {
+                anchored = true;
+            },
+            Some("super") => {
+                mod_path.pop();
+                anchored = true;
+            },
+            _ => break,
+        }
+        rest.remove(0);
+    }
+    let rest = rest.join("::");
+    loop {
+        let candidate = mod_name_fmt(&mod_path.join("::"), &rest);
+        if exists(&candidate) {
+            return candidate
+        }
+        // use aliases: `use shapes::Rect;` then `Rect`
+        let (head, tail) = match rest.find("::") {
+            Some(pos) => (rest[..pos].to_string(), rest[pos..].to_string()),
+            None => (rest.clone(), String::new()),
+        };
+        if let Some(full) = data.use_alias.get(&mod_name_fmt(&mod_path.join("::"), &head)) {
+            let candidate = format!("{}{}", full, tail);
+            if exists(&candidate) {
+                return candidate
+            }
+        }
+        if anchored || mod_path.pop().is_none() {
+            break
+        }
+    }
+    name.to_string()
+}
+
+fn use_alias_collect(items: &Vec<syn::Item>, data: &mut data_pkg) {
+    // `use` declarations apply to their whole module, register them
+    // before any path is resolved
+    for item in items {
+        match item {
+            Item::Use(itemuse) => {
+                let mut aliases = Vec::new();
+                use_collect(&itemuse.tree, String::new(), &mut aliases);
+                for (alias, full) in aliases {
+                    data.use_alias.insert(mod_name_fmt(&data.mod_path.join("::"), &alias), full);
+                }
+            },
+            Item::Mod(itemmod) => {
+                if let Some((_, mod_items)) = &itemmod.content {
+                    data.mod_path.push(format!("{}", itemmod.ident));
+                    use_alias_collect(mod_items, data);
+                    data.mod_path.pop();
+                }
+            },
+            _ => {}
+        }
+    }
+}
+
+fn use_collect(tree: &syn::UseTree, prefix: String, aliases: &mut Vec<(String, String)>) {
+    // flatten a use tree into (alias, full path) pairs,
+    // globs are not followed
+    match tree {
+        syn::UseTree::Path(use_path) => {
+            let ident = format!("{}", use_path.ident);
+            let prefix = if ident == "crate" || ident == "self" { prefix } else { mod_name_fmt(&prefix, &ident) };
+            use_collect(&use_path.tree, prefix, aliases);
+        },
+        syn::UseTree::Name(use_name) => {
+            let ident = format!("{}", use_name.ident);
+            aliases.push((ident.clone(), mod_name_fmt(&prefix, &ident)));
+        },
+        syn::UseTree::Rename(use_rename) => {
+            aliases.push((format!("{}", use_rename.rename), mod_name_fmt(&prefix, &format!("{}", use_rename.ident))));
+        },
+        syn::UseTree::Group(use_group) => {
+            for tree in &use_group.items {
+                use_collect(tree, prefix.clone(), aliases);
+            }
+        },
+        syn::UseTree::Glob(_) => info!("use glob not supported"),
+    }
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Generate annotated source and write into file
+///
+/// # FileName
+///
+/// PathBuf::from(source_file_destination)
+///
+/// # color_info
+///
+/// color_info
+pub fn asource_gen(FileName : &PathBuf, color_info: &Vec<HashMap<String, Vec<StackItem>>>, var_map: &HashMap<String, ResourceAccessPoint>) -> Result<String, Box<dyn Error>>{
     let mut insert_holder: BTreeMap<usize, BTreeMap<usize, String>> = BTreeMap::new();
     fn insert(insert_holder: &mut BTreeMap<usize, BTreeMap<usize, String>>, row: usize, col:usize, content: String) {
         match insert_holder.get_mut(&row) {
             Some(col_map) => {
-                col_map.insert(col, content);
+                // several tags on one column (shorthand field `Point { x }`
+                // is both a member and a use of x): close before open
+                let tags = col_map.entry(col).or_insert(String::new());
+                if content == "</tspan>" {
+                    tags.insert_str(0, &content);
+                } else {
+                    tags.push_str(&content);
+                }
             },
             None => {
                 let mut col_map = BTreeMap::new();
@@ -153,16 +882,25 @@ pub fn asource_gen(FileName : &PathBuf, color_info: &Vec<HashMap<String, Vec<Sta
                 let hash_id = rap_item.hash();
                 for item in stack_vec {
                     println!("{:?}", item);
+                    if !same_file(&item.SrcFile, FileName) {
+                        // occurrence lives in another module file
+                        continue
+                    }
                     match &item.SynInfo {
                         Infoitem::Struct(itemstruct) => {
                             let tag = format!("<tspan data-hash=\"{}\">", hash_id);
+                            // tuple struct fields have no name, use the type
+                            let field_span = match &itemstruct.ident {
+                                Some(ident) => ident.span(),
+                                None => itemstruct.ty.span(),
+                            };
                             insert(&mut insert_holder,
-                                itemstruct.ident.span().start().line,
-                                itemstruct.ident.span().start().column,
+                                field_span.start().line,
+                                field_span.start().column,
                                 tag);
                             insert(&mut insert_holder,
-                                itemstruct.ident.span().end().line,
-                                itemstruct.ident.span().end().column,
+                                field_span.end().line,
+                                field_span.end().column,
                                 String::from("</tspan>"));
                         },
                         Infoitem::Func(itemfunc) => {
@@ -254,22 +992,66 @@ pub fn asource_gen(FileName : &PathBuf, color_info: &Vec<HashMap<String, Vec<Sta
                                 itemstuexp.span().end().column,
                                 String::from("</tspan>"));
                         },
-                        Infoitem::Macro(itemmacro) => {
-                            let tag = format!("<tspan class=\"fn\" data-hash=\"0\" hash=\"{}\">", hash_id);
+                        Infoitem::TupleArg(itemarg) => {
+                            let tag = format!("<tspan data-hash=\"{}\">", hash_id);
                             insert(&mut insert_holder,
-                                itemmacro.ident.span().start().line,
-                                itemmacro.ident.span().start().column,
+                                itemarg.span().start().line,
+                                itemarg.span().start().column,
                                 tag);
                             insert(&mut insert_holder,
-                                itemmacro.ident.span().end().line,
-                                itemmacro.ident.span().end().column+1, // compensate for '!'
+                                itemarg.span().end().line,
+                                itemarg.span().end().column,
                                 String::from("</tspan>"));
                         },
-                        Infoitem::Path(itempath) => {
+                        Infoitem::Global(itemglobal) => {
                             let tag = format!("<tspan data-hash=\"{}\">", hash_id);
                             insert(&mut insert_holder,
-                                itempath.span().start().line,
-                                itempath.span().start().column,
+                                itemglobal.span().start().line,
+                                itemglobal.span().start().column,
+                                tag);
+                            insert(&mut insert_holder,
+                                itemglobal.span().end().line,
+                                itemglobal.span().end().column,
+                                String::from("</tspan>"));
+                        },
+                        Infoitem::StrLit(itemlit) => {
+                            let tag = format!("<tspan data-hash=\"{}\">", hash_id);
+                            insert(&mut insert_holder,
+                                itemlit.span().start().line,
+                                itemlit.span().start().column,
+                                tag);
+                            insert(&mut insert_holder,
+                                itemlit.span().end().line,
+                                itemlit.span().end().column,
+                                String::from("</tspan>"));
+                        },
+                        Infoitem::Try(itemtry) => {
+                            let tag = format!("<tspan data-hash=\"{}\">", hash_id);
+                            insert(&mut insert_holder,
+                                itemtry.span().start().line,
+                                itemtry.span().start().column,
+                                tag);
+                            insert(&mut insert_holder,
+                                itemtry.span().end().line,
+                                itemtry.span().end().column,
+                                String::from("</tspan>"));
+                        },
+                        Infoitem::Macro(itemmacro) => {
+                            let tag = format!("<tspan class=\"fn\" data-hash=\"0\" hash=\"{}\">", hash_id);
+                            insert(&mut insert_holder,
+                                itemmacro.ident.span().start().line,
+                                itemmacro.ident.span().start().column,
+                                tag);
+                            insert(&mut insert_holder,
+                                itemmacro.ident.span().end().line,
+                                itemmacro.ident.span().end().column+1, // compensate for '!'
+                                String::from("</tspan>"));
+                        },
+                        Infoitem::Path(itempath) => {
+                            let tag = format!("<tspan data-hash=\"{}\">", hash_id);
+                            insert(&mut insert_holder,
+                                itempath.span().start().line,
+                                itempath.span().start().column,
                                 tag);
                             insert(&mut insert_holder,
                                 itempath.span().end().line,
@@ -322,7 +1104,7 @@ pub fn asource_gen(FileName : &PathBuf, color_info: &Vec<HashMap<String, Vec<Sta
             Err(ref e) => {println!("error parsing header: {:?}", e)},
         }
         cursor = 0;
-        output.push_str("\n");
+        output.push('\n');
         line_num+=1;
     }
     output.pop();
@@ -346,19 +1128,20 @@ pub fn asource_gen(FileName : &PathBuf, color_info: &Vec<HashMap<String, Vec<Sta
     // }
 }
 
-pub fn header_gen_str(var_alloc: &HashMap<String, Vec<Arc<ResourceAccessPoint>>>) -> String {
-    // generate header lines 
+pub fn header_gen_str(res: &mut ParseResult) -> String {
+    // generate header lines, what cannot be written out is
+    // reported in res.diagnostics on line 0
     let mut header = String::new();
     let mut struct_store: Vec<Struct> = Vec::new();
 
     header.push_str("/* --- BEGIN Variable Definitions ---\n");
-    for (_, value) in var_alloc {
+    for value in res.var_alloc.values() {
         for i in value {
             header.push_str(&i.rap_header(&mut struct_store));
         }
     }
     // deal with structs
-    let mut struct_owner: HashMap<u64, String> = HashMap::new();
+    let mut struct_owner: BTreeMap<u64, String> = BTreeMap::new();
     let mut struct_member: HashMap<u64, Vec<String>> = HashMap::new();
 
     for i in struct_store {
@@ -377,64 +1160,123 @@ pub fn header_gen_str(var_alloc: &HashMap<String, Vec<Arc<ResourceAccessPoint>>>
     }
 
     for (key, val) in struct_owner {
-        header.push_str(&format!("Struct {}{{", val));
-        match struct_member.get(&key) {
-            Some(member_vec) => {
-                for i in member_vec {
-                    header.push_str(&format!("{},",i));
+        let struct_type = match res.rap_info.get(&key) {
+            Some(info) => info.ty.split('<').next().unwrap_or("").to_string(),
+            None => String::new(),
+        };
+        let mut member_vec = match struct_member.remove(&key) {
+            Some(member_vec) => member_vec,
+            None => {
+                // unit struct, or no member was recorded for it
+                let has_fields = res.structs.get(&struct_type).is_some_and(|def| !def.fields.is_empty());
+                if has_fields {
+                    diag_insert(&res.files[0], 0, format!("no member recorded for struct {}, emitting Struct {}{{}}", val, val), &mut res.diagnostics);
                 }
+                Vec::new()
             }
+        };
+        // declaration order, nested members (start.x) follow their parent member (start)
+        member_vec.sort_by_key(|member| (member_order(&struct_type, member, &res.structs), member.clone()));
+        member_vec.dedup();
+        header.push_str(&format!("Struct {}{{{}}};\n", val, member_vec.join(",")));
+    }
+    for (key, member_vec) in struct_member {
+        // members whose owner is not a struct in var_alloc
+        diag_insert(&res.files[0], 0, format!("members {:?} have no struct owner (hash {}), skipped", member_vec, key), &mut res.diagnostics);
+    }
+    header.push_str("--- END Variable Definitions --- */\n");
+    header
+}
+
+fn member_order(struct_type: &str, member: &str, structs: &HashMap<String, StructDef>) -> Vec<usize> {
+    // position of every field of a member in its struct definition:
+    // start.x of a Line -> [0, 0], unknown fields sort last
+    let mut order = Vec::new();
+    let mut ty = struct_type.to_string();
+    for field_name in member.split('.') {
+        match structs.get(&ty).and_then(|def| def.fields.iter().position(|(name, _)| name == field_name)) {
+            Some(pos) => {
+                order.push(pos);
+                ty = structs[&ty].fields[pos].1.split('<').next().unwrap_or("").to_string();
+            },
             None => {
-                //Error!
+                order.push(usize::MAX);
+                ty = String::new();
             }
         }
-        header.pop();
-        header.push_str("};\n");
     }
-    header.push_str("--- END Variable Definitions --- */\n");
-    header
+    order
 }
 
-fn struct_expr_insert(syn_info: Infoitem,
-    struct_name: String,
+fn struct_member_insert(struct_name: &String,
     mut target_rap: ResourceAccessPoint,
     data: &mut data_pkg,
-    stack_num: usize,
-    owner_name: &String) {
-        // used for inserting struct expression:
-        // ex: Point { x: 1, y: 1 }
-        let mut rap_arc: Option<Arc<ResourceAccessPoint>> = None;
-        match data.var_def.get(&struct_name) {
+    owner_name: &String,
+    line: usize) -> Option<(Arc<ResourceAccessPoint>, Arc<ResourceAccessPoint>)> {
+        // register owner.field as a member of a struct instance,
+        // the member takes the hash of the field definition
+        // returns (field definition, member)
+        let rap_arc;
+        match data.var_def.get(struct_name) {
             Some(field) => {
                 match field.get(target_rap.name()) {
                     Some(res) => {
-                        target_rap.hash_mod(res.hash().clone());
-                        rap_arc = Some(res.clone());
+                        target_rap.hash_mod(*res.hash());
+                        rap_arc = res.clone();
                     },
                     _ => {
-                        //ERROR!
+                        diag_insert(&data.cur_file, line, format!("field {} not found in struct {}", target_rap.name(), struct_name), &mut data.diagnostics);
+                        return None
                     }
                 }
             },
             _ => {
-                //ERROR!
+                diag_insert(&data.cur_file, line, format!("struct {} not found", struct_name), &mut data.diagnostics);
+                return None
             }
         }
 
-        if data.var_alloc.contains_key(target_rap.name()) {
+        let member_key = format!("{}.{}", owner_name, target_rap.name());
+        if let (Some(pos), ResourceAccessPoint::Struct(member)) = (owner_name.find('.'), &mut target_rap) {
+            // nested member l.start.x is named start.x inside its root owner l
+            member.name = format!("{}.{}", &owner_name[pos+1..], member.name);
+        }
+        let member_arc = Arc::new(target_rap.clone());
+        // members are keyed by owner.field so that every instance keeps its
+        // members and a local with the same name as a field is not hidden
+        if data.var_alloc.contains_key(&member_key) {
             // TODO: add shadow RAP
             // var_def[&get_identstr(&target_rap)].push(target_rap);
         } else {
             // add RAP
-            data.var_alloc.insert(target_rap.name().clone(), vec![Arc::new(target_rap.clone())]);
+            data.var_alloc.insert(member_key.clone(), vec![member_arc.clone()]);
         }
+        data.members.insert(member_key, member_arc.clone());
+        Some((rap_arc, member_arc))
+    }
+
+fn struct_expr_insert(syn_info: Infoitem,
+    struct_name: String,
+    target_rap: ResourceAccessPoint,
+    data: &mut data_pkg,
+    stack_num: usize,
+    owner_name: &String) {
+        // used for inserting struct expression:
+        // ex: Point { x: 1, y: 1 }
+        let field_name = target_rap.name().clone();
+        let line = info_line(&syn_info);
+        let rap_arc = match struct_member_insert(&struct_name, target_rap, data, owner_name, line) {
+            Some((rap_arc, _)) => rap_arc,
+            None => return,
+        };
 
         let stack_item = StackItem {
             SynInfo: syn_info,
-            ItemOrig: Arc::clone(&rap_arc.unwrap()),
+            ItemOrig: Arc::clone(&rap_arc),
+            SrcFile: data.cur_file.clone(),
         };
         // push into stack
-        let stut_fieldname = format!("{}.{}",owner_name, target_rap.name());
+        let stut_fieldname = format!("{}.{}",owner_name, field_name);
         match data.color_info[stack_num].get_mut(&stut_fieldname) {
             Some(var_map) => {
                 var_map.push(stack_item);
@@ -445,6 +1287,33 @@ fn struct_expr_insert(syn_info: Infoitem,
         }
     }
 
+fn member_move_insert(from: Option<Arc<ResourceAccessPoint>>,
+    struct_type: &String,
+    field_name: &String,
+    owner_name: &String,
+    line: usize,
+    data: &mut data_pkg) {
+    // a struct member gets its value from another resource:
+    // Move, or Copy when the declared field type is Copy
+    if from.is_none() {
+        return
+    }
+    let to = match data.members.get(&format!("{}.{}", owner_name, field_name)) {
+        Some(member) => Some(member.clone()),
+        None => return,
+    };
+    let is_copy = match data.struct_info.get(struct_type) {
+        Some(def) => def.fields.iter().any(|(name, ty)| name == field_name && type_is_copy(ty)),
+        None => false,
+    };
+    let event = if is_copy {
+        EventItem::Copy { from, to }
+    } else {
+        EventItem::Move { from, to }
+    };
+    event_insert(line, event, data);
+}
+
 fn struct_def_insert(syn_info: Infoitem,
     struct_type: String,
     target_rap: ResourceAccessPoint,
@@ -455,6 +1324,7 @@ fn struct_def_insert(syn_info: Infoitem,
         let stack_item = StackItem {
             SynInfo: syn_info,
             ItemOrig: Arc::clone(&rap_arc),
+            SrcFile: data.cur_file.clone(),
         };
 
         match data.var_def.get_mut(&struct_type) {
@@ -481,10 +1351,11 @@ fn struct_def_insert(syn_info: Infoitem,
 fn var_allo_insert(syn_info: Infoitem, 
     target_rap: ResourceAccessPoint,
     data: &mut data_pkg,
-    stack_num: usize) {
+    stack_num: usize) -> Option<Arc<ResourceAccessPoint>> {
     // variable initialization happened -> 
     // look for RAP, if exist then add shadowing RAP, else add RAP to var_def
     // push into color_info
+    // returns the registered RAP
     // called for the following InfoItem:
     // ----------------------------------
     // Func(syn::ItemFn)
@@ -494,14 +1365,16 @@ fn var_allo_insert(syn_info: Infoitem,
     // is_def is true for struct declaraion
     // Struct(syn::ItemStruct)
     // ----------------------------------
-    if target_rap.name() == &"main()" {
+    if target_rap.name() == "main()" {
         // avoid main() here
-        return
+        return None
     }
     let rap_arc = Arc::new(target_rap.clone());
+    data.last_use.insert(*rap_arc.hash(), info_line(&syn_info));
     let stack_item = StackItem {
         SynInfo: syn_info,
         ItemOrig: Arc::clone(&rap_arc),
+        SrcFile: data.cur_file.clone(),
     };
 
     if data.var_alloc.contains_key(target_rap.name()) {
@@ -509,7 +1382,7 @@ fn var_allo_insert(syn_info: Infoitem,
         // var_def[&get_identstr(&target_rap)].push(target_rap);
     } else {
         // add RAP
-        data.var_alloc.insert(target_rap.name().clone(), vec![rap_arc]);
+        data.var_alloc.insert(target_rap.name().clone(), vec![rap_arc.clone()]);
     }
     
     // push into stack
@@ -521,6 +1394,7 @@ fn var_allo_insert(syn_info: Infoitem,
             data.color_info[stack_num].insert(target_rap.name().clone(), vec![stack_item]);
         }
     }
+    Some(rap_arc)
 }
 
 fn non_allo_insert(ident: String,
@@ -528,22 +1402,20 @@ fn non_allo_insert(ident: String,
     target_rap: Option<ResourceAccessPoint>,
     data: &mut data_pkg,
     hash_num: &mut u64,
-    stack_num: usize) {
+    stack_num: usize) -> Option<Arc<ResourceAccessPoint>> {
     // variable initialization did not happen -> 
     // search stack for reference to RAP
     // search upon the nearest stack with the same ident -> 
     // if multiple item correspond to one ident then choose the last
     // not found then add RAP and call var_allo_insert()
+    // returns the RAP the ident resolved to
     // called for the following InfoItem:
     // ----------------------------------
     // Call(syn::ExprCall), // func_cal();
     // MethodCall(syn::ExprMethodCall), // a.to_string();
     // Reference(syn::ExprReference), // &a;
     // ----------------------------------
-    let mut rap_arc: Option<Arc<ResourceAccessPoint>> = None;
-    let stack_item;
-
-    if let Some(mut tar_rap) = target_rap {
+    let rap_arc = if let Some(tar_rap) = target_rap {
         // ----------------------------------
         // Call(syn::ExprCall), // func_cal();
         // MethodCall(syn::ExprMethodCall), // a.to_string();
@@ -552,34 +1424,38 @@ fn non_allo_insert(ident: String,
         match data.var_alloc.get(&ident) {
             Some(rap_vec) => {
                 //TODO: shadowing variable
-                rap_arc = Some(rap_vec[0].clone());
+                rap_vec[0].clone()
             },
             _ => {
                 *hash_num+=1;
                 let rap_alloc = Arc::new(tar_rap);
-                rap_arc = Some(rap_alloc.clone());
-                data.var_alloc.insert(ident.clone(), vec![rap_alloc]);
+                data.var_alloc.insert(ident.clone(), vec![rap_alloc.clone()]);
+                rap_alloc
             }
         }
     } else {
         // ----------------------------------
         // Reference(syn::ExprReference), // &a;
         // ----------------------------------
-        match data.var_alloc.get(&ident) {
-            Some(rap_vec) => {
-                //TODO: shadowing variable
-                rap_arc = Some(rap_vec[0].clone());
-            },
+        match rap_lookup(&ident, data, stack_num) {
+            Some(rap) => rap,
             _ => {
                 //Error!!
                 println!("undefined variable {} found", ident);
+                return None
             }
         }
-    }
+    };
 
-    stack_item = StackItem {
+    let line = info_line(&syn_info);
+    let last = data.last_use.entry(*rap_arc.hash()).or_insert(line);
+    if *last < line {
+        *last = line;
+    }
+    let stack_item = StackItem {
         SynInfo: syn_info,
-        ItemOrig: Arc::clone(&rap_arc.unwrap()),
+        ItemOrig: Arc::clone(&rap_arc),
+        SrcFile: data.cur_file.clone(),
     };
 
     // push into stack
@@ -591,153 +1467,1732 @@ fn non_allo_insert(ident: String,
             data.color_info[stack_num].insert(ident.clone(), vec![stack_item]);
         }
     }
+    Some(rap_arc)
 }
 
-//TODO: do I need to specify same lifetime for color_info and var_def
-fn parse_item (items: &Vec<syn::Item>, 
+fn event_insert(line: usize,
+    event: EventItem,
+    data: &mut data_pkg) {
+    // record an event, remembering resources that are moved out
+    debug!("event at line {}: {:?}", line, event);
+    match &event {
+        EventItem::PassByMoveTo { from: Some(from), .. } |
+        EventItem::Move { from: Some(from), .. } => {
+            data.moved.insert(*from.hash());
+        },
+        _ => {}
+    }
+    data.events.push((data.cur_file.clone(), line, event));
+}
+
+fn diag_insert(file: &Path, line: usize, msg: String, diagnostics: &mut Vec<(PathBuf, usize, String)>) {
+    // report something the parser could not model, keeping going
+    debug!("{:?} line {}: {}", file, line, msg);
+    diagnostics.push((file.to_path_buf(), line, msg));
+}
+
+fn scope_enter(block: &syn::Block, data: &mut data_pkg) {
+    // open a scope frame that closes at the block's '}'
+    data.scopes.push(ScopeFrame {
+        end_line: block.brace_token.span.end().line,
+        decls: Vec::new(),
+        is_fn: false,
+    });
+}
+
+fn exit_target(label: Option<&syn::Lifetime>, data: &data_pkg) -> Option<usize> {
+    // the loop_exits entry break / continue leaves: the innermost loop,
+    // or the loop or block labelled 'outer for break 'outer
+    let label = label.map(|label| format!("{}", label));
+    data.loop_exits.iter().rposition(|exit| match &label {
+        Some(label) => exit.label.as_ref() == Some(label),
+        None => exit.is_loop,
+    })
+}
+
+fn scope_jump(exit: Option<usize>,
+    carried: &[u64],
+    line: usize,
+    data: &mut data_pkg) {
+    // break 'outer / continue: everything declared in the scopes left
+    // on line may go out of scope there instead of at their closing brace,
+    // except what was moved or what break carries out
+    let frame = match exit.and_then(|pos| data.loop_exits.get(pos)) {
+        Some(exit) => exit.scope,
+        None => return,
+    };
+    let left: Vec<u64> = data.scopes.iter().skip(frame)
+        .flat_map(|frame| frame.decls.iter().map(|rap| *rap.hash()))
+        .filter(|hash| !data.moved.contains(hash) && !carried.contains(hash))
+        .collect();
+    for hash in left {
+        let exits = &mut data.rap_info.entry(hash).or_default().exits;
+        if !exits.contains(&line) {
+            exits.push(line);
+        }
+    }
+}
+
+fn scope_decl(rap_arc: &Arc<ResourceAccessPoint>, data: &mut data_pkg) {
+    // an owner or reference is declared in the innermost scope
+    if let Some(frame) = data.scopes.last_mut() {
+        frame.decls.push(rap_arc.clone());
+    }
+}
+
+fn scope_exit(data: &mut data_pkg) {
+    // close the innermost scope: everything declared in it goes out of
+    // scope at the closing brace, in reverse declaration order (drop order),
+    // except the resources that were moved out earlier
+    if let Some(frame) = data.scopes.pop() {
+        // a let without value that was never assigned holds nothing
+        let depth = data.scopes.len();
+        data.uninit.retain(|_, pending| pending.scope < depth);
+        for rap_arc in frame.decls.iter().rev() {
+            if data.moved.contains(rap_arc.hash()) {
+                continue
+            }
+            event_insert(frame.end_line, EventItem::GoOutOfScope { ro: rap_arc.clone() }, data);
+        }
+    }
+}
+
+fn info_line(syn_info: &Infoitem) -> usize {
+    // source line an Infoitem starts on
+    match syn_info {
+        Infoitem::Struct(itemstruct) => itemstruct.span().start().line,
+        Infoitem::Func(itemfunc) => itemfunc.sig.ident.span().start().line,
+        Infoitem::FnArg(itemarg) => itemarg.span().start().line,
+        Infoitem::Local(itemlocal) => itemlocal.ident.span().start().line,
+        Infoitem::Call(itemcall) => itemcall.span().start().line,
+        Infoitem::MethodCall(itemmcall) => itemmcall.method.span().start().line,
+        Infoitem::Reference(itemref) => itemref.span().start().line,
+        Infoitem::Dereference(itemref) => itemref.span().start().line,
+        Infoitem::ExprStruct(itemstuexp) => itemstuexp.span().start().line,
+        Infoitem::TupleArg(itemarg) => itemarg.span().start().line,
+        Infoitem::Global(itemglobal) => itemglobal.span().start().line,
+        Infoitem::StrLit(itemlit) => itemlit.span().start().line,
+        Infoitem::Try(itemtry) => itemtry.span().start().line,
+        Infoitem::Macro(itemmacro) => itemmacro.ident.span().start().line,
+        Infoitem::Path(itempath) => itempath.span().start().line,
+    }
+}
+
+fn ref_die_gen(data: &mut data_pkg) {
+    // non-lexical lifetimes: every reference declared in the function
+    // dies on the line it is last used, not at the end of its block
+    let fn_refs = std::mem::take(&mut data.fn_refs);
+    for ref_rap in fn_refs {
+        if data.moved.contains(ref_rap.hash()) {
+            // a &mut moved into another reference: that one dies instead
+            continue
+        }
+        let line = match data.last_use.get(ref_rap.hash()) {
+            Some(line) => *line,
+            None => continue,
+        };
+        let targets = match data.ref_targets.get(ref_rap.hash()) {
+            Some(targets) => targets.iter().map(|target| Some(target.clone())).collect(),
+            None => vec![None], // reference parameter, owner unknown
+        };
+        for target in targets {
+            let from = Some(ref_rap.clone());
+            let event = match &*ref_rap {
+                ResourceAccessPoint::MutRef(_) => EventItem::MutableDie { from, to: target },
+                _ => EventItem::StaticDie { from, to: target },
+            };
+            event_insert(line, event, data);
+        }
+    }
+}
+
+fn owner_lookup(ident: &String,
+    data: &data_pkg,
+    stack_num: usize) -> Option<Arc<ResourceAccessPoint>> {
+    // the RAP an identifier refers to, if it owns its resource
+    match rap_lookup(ident, data, stack_num) {
+        Some(rap) => match &*rap {
+            ResourceAccessPoint::Owner(_) => Some(rap),
+            ResourceAccessPoint::Struct(owner) if !owner.is_member => Some(rap),
+            _ => None,
+        },
+        None => None,
+    }
+}
+
+fn lit_ty(lit: &syn::Lit) -> String {
+    // type of a literal, with the integer/float defaults of rustc
+    match lit {
+        syn::Lit::Str(_) => String::from("&'static str"),
+        syn::Lit::Int(lit_int) if lit_int.suffix().is_empty() => String::from("i32"),
+        syn::Lit::Int(lit_int) => lit_int.suffix().to_string(),
+        syn::Lit::Float(lit_float) if lit_float.suffix().is_empty() => String::from("f64"),
+        syn::Lit::Float(lit_float) => lit_float.suffix().to_string(),
+        syn::Lit::Bool(_) => String::from("bool"),
+        syn::Lit::Char(_) => String::from("char"),
+        _ => String::new(),
+    }
+}
+
+fn rap_lookup(ident: &String,
+    data: &data_pkg,
+    stack_num: usize) -> Option<Arc<ResourceAccessPoint>> {
+    // find the RAP an identifier currently refers to:
+    // search color_info from the nearest stack down and take the
+    // last item registered under the ident, then fall back to var_alloc
+    if !data.color_info.is_empty() {
+        let top = std::cmp::min(stack_num, data.color_info.len()-1);
+        for i in (0..top+1).rev() {
+            if let Some(stack_vec) = data.color_info[i].get(ident) {
+                if let Some(item) = stack_vec.last() {
+                    return Some(Arc::clone(&item.ItemOrig));
+                }
+            }
+        }
+    }
+    data.var_alloc.get(ident).map(|rap_vec| rap_vec[0].clone())
+}
+
+fn call_arg_insert(arg: &syn::Expr,
+    param: &ResourceAccessPoint,
+    param_ty: &str,
     data: &mut data_pkg,
-    hash_num: &mut u64,
     stack_num: usize) {
-    for item in items {
-        // All items refer to https://docs.rs/syn/1.0.72/syn/enum.Item.html
-        // TODO: macros, const, enums, structs... we only consider functions and struct here
-        // TODO: Add errors???
+    // match a call argument against the callee's parameter:
+    // take(s)        -> PassByMoveTo
+    // takes(n: i32)  -> Copy, n stays usable
+    // borrow(&s)     -> PassByStaticReference
+    // change(&mut s) -> PassByMutableReference
+    // the callee signature decides the kind, the argument only
+    // names the caller variable
+    let arg_value = match arg {
+        Expr::Reference(expred) => &*expred.expr,
+        _ => arg,
+    };
+    let arg_name = match arg_value {
+        Expr::Path(exprpath) => path_fmt(exprpath),
+        // sum(&v[1..3]) borrows v
+        Expr::Index(_) => match expr_base(arg_value) {
+            Some(base) => base,
+            None => return,
+        },
+        _ => {
+            // struct literal argument: draw(Point { x: 1, y: 1 })
+            match struct_lit_resolve(arg_value, data) {
+                Some(struct_type) => tmp_owner_name(arg_value, &struct_type),
+                None => return,
+            }
+        },
+    };
+    let from = match rap_lookup(&arg_name, data, stack_num) {
+        Some(rap) => Some(rap),
+        None => return,
+    };
+    let to = Some(Arc::new(param.clone()));
+    let event = match param {
+        ResourceAccessPoint::MutRef(_) => EventItem::PassByMutableReference { from, to },
+        ResourceAccessPoint::StaticRef(_) => EventItem::PassByStaticReference { from, to },
+        _ if type_is_copy(param_ty) => EventItem::Copy { from, to },
+        _ => EventItem::PassByMoveTo { from, to },
+    };
+    event_insert(arg.span().start().line, event, data);
+}
 
-        // TODO: *item and func.clone / item and *func.clone 
-        // match take ownership???
-        // clone of a reference??? or of the variable itself?
-        match item {
-            Item::Fn(func) => {
-                // register func into var_def
-                let func_rap = ResourceAccessPoint::Function(Function{name: format!("{}()", func.sig.ident), hash: hash_num.clone()});
+fn type_is_copy(ty: &str) -> bool {
+    // whether a written type is Copy: primitives, shared references,
+    // raw pointers, and Option / tuples / arrays of Copy types
+    match ty {
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize"
+        | "u8" | "u16" | "u32" | "u64" | "u128" | "usize"
+        | "f32" | "f64" | "bool" | "char" | "()" => true,
+        _ if ty.starts_with("Option<") && ty.ends_with('>') => type_is_copy(&ty[7..ty.len()-1]),
+        _ if ty.starts_with('(') && ty.ends_with(')') => {
+            ty_args(&ty[1..ty.len()-1]).iter().all(|arg| type_is_copy(arg))
+        },
+        _ if ty.starts_with('[') && ty.ends_with(']') && ty.contains(';') => {
+            type_is_copy(&ty_args(&ty[1..ty.len()-1].replace(';', ","))[0])
+        },
+        _ if ty.starts_with('&') => {
+            // &'a mut T is as unique as &mut T
+            let rest = ty[1..].trim_start();
+            let rest = if rest.starts_with('\'') { rest.split_once(' ').map(|(_, rest)| rest).unwrap_or("") } else { rest };
+            !rest.starts_with("mut ")
+        },
+        _ => ty.starts_with("*const ") || ty.starts_with("*mut "),
+    }
+}
+
+fn ty_args(args: &str) -> Vec<String> {
+    // split a list of types at its top level commas:
+    // "Vec<u8>, (i32, i32)" -> ["Vec<u8>", "(i32, i32)"]
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (pos, c) in args.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(args[start..pos].trim().to_string());
+                start = pos + 1;
+            },
+            _ => {}
+        }
+    }
+    parts.push(args[start..].trim().to_string());
+    parts
+}
+
+fn fn_sig_gen(sig: &syn::Signature,
+    func_name: String,
+    hash_num: &mut u64,
+    file: &Path,
+    diagnostics: &mut Vec<(PathBuf, usize, String)>) -> FnSig {
+    // allocate RAPs for a function definition and its arguments
+    let func_rap = ResourceAccessPoint::Function(Function{name: func_name, hash: *hash_num});
+    *hash_num+=1;
+    let generics = generic_bounds(&sig.generics);
+    let mut args = Vec::new();
+    let mut arg_bounds = HashMap::new();
+    for (pos, arg) in sig.inputs.iter().enumerate() {
+        match arg {
+            FnArg::Receiver(receiver) => {
+                // &self / &mut self / self / mut self
+                let p_ident = Ident::new("self", receiver.self_token.span);
+                let is_mut = receiver.mutability.is_some();
+                let (arg_rap, arg_ty) = match &receiver.reference {
+                    Some((_, lifetime)) => {
+                        let lifetime = lifetime.as_ref().map(|lifetime| format!("{} ", lifetime)).unwrap_or_default();
+                        if is_mut {
+                            (ResourceAccessPoint::MutRef(MutRef {name: String::from("self"), hash: *hash_num, is_mut: false}),
+                            format!("&{}mut Self", lifetime))
+                        } else {
+                            (ResourceAccessPoint::StaticRef(StaticRef {name: String::from("self"), hash: *hash_num, is_mut: false}),
+                            format!("&{}Self", lifetime))
+                        }
+                    },
+                    None => (ResourceAccessPoint::Owner(Owner {name: String::from("self"), hash: *hash_num, is_mut}),
+                        String::from("Self")),
+                };
                 *hash_num+=1;
+                args.push((pos, p_ident, arg_rap, arg_ty));
+            },
+            FnArg::Typed(pat_type) => {
                 debug!("--------------");
-                debug!("func found: {:?}", func_rap);
-                debug!("{:?}", func.span().start());
-                debug!("{:?}", func.span().end());
+                debug!("{:?}", pat_type.span().start());
+                debug!("{:?}", pat_type.span().end());
                 debug!("--------------");
-                // push stack and register func into color_info
-                data.color_info.push(HashMap::new());
-                var_allo_insert(Infoitem::Func(func.clone()), 
-                func_rap, data, stack_num);
-
-                if func.sig.inputs.len() != 0 {
-                    // match arguments
-                    // create new stack for func arg
-                    for arg in &func.sig.inputs {
-                        match arg {
-                            FnArg::Typed(pat_type) => {
-                                // match arg type
-                                let mut func_argname = String::new();
-                                let mut is_mut = false;
-                                let mut p_ident = Ident::new("calligraphy", Span::call_site());
-                                debug!("--------------");
-                                // extract arg ident
-                                match &*pat_type.pat {
-                                    Pat::Ident(pat_ident) => {
-                                        // push var into stack
-                                        func_argname = String::from(format!("{}", pat_ident.ident));
-                                        if let Some(_mutability) = &pat_ident.mutability {
-                                            is_mut = true;
-                                        }
-                                        p_ident = pat_ident.ident.clone();
-                                        // debug!("arg found: {:?}", func_arg.name);
-                                    },
-                                    _ => info!("function arg name not supported")
-                                }
-                                debug!("{:?}", pat_type.span().start());
-                                debug!("{:?}", pat_type.span().end());
-                                debug!("--------------");
-                                // extract arg type
-                                // TODO: fix this
-                                let mut arg_rap = ResourceAccessPoint::Owner(Owner {name: String::new(), hash: 0, is_mut: false});
-                                match &*pat_type.ty {
-                                    Type::Reference(type_reference) => {                                  
-                                        if let Some(_mutability) = &type_reference.mutability {
-                                            arg_rap = ResourceAccessPoint::MutRef(MutRef {name: func_argname.clone(), hash: hash_num.clone(), is_mut: is_mut});
-                                            *hash_num+=1;
-                                        } else {
-                                            arg_rap = ResourceAccessPoint::StaticRef(StaticRef {name: func_argname.clone(), hash: hash_num.clone(), is_mut: is_mut});
-                                            *hash_num+=1;
-                                        }
-                                    },
-                                    Type::Path(_) => {
-                                        arg_rap = ResourceAccessPoint::Owner(Owner {name: func_argname.clone(), hash: hash_num.clone(), is_mut: is_mut});
-                                        *hash_num+=1;
-                                    }
-                                    _ => info!("function arg type not supported")
-                                }
-                                var_allo_insert(Infoitem::FnArg(p_ident), 
-                                arg_rap, data, stack_num+1);
-                            },
-                            _ => info!("syn::Receiver <self> not supported")
-                        }
+                // extract arg ident
+                let pat_ident = match &*pat_type.pat {
+                    Pat::Ident(pat_ident) => pat_ident,
+                    _ => {
+                        diag_insert(file, pat_type.span().start().line,
+                            format!("parameter pattern {} of {} not supported, no RAP created",
+                            pat_type.pat.to_token_stream(), func_rap.name()), diagnostics);
+                        continue
                     }
+                };
+                let func_argname = format!("{}", pat_ident.ident);
+                let is_mut = pat_ident.mutability.is_some();
+                // extract arg type: borrowed (shared or mut) or owned
+                let arg_rap = match arg_kind(&pat_type.ty) {
+                    Some(RecvKind::MutBorrow) => ResourceAccessPoint::MutRef(MutRef {name: func_argname.clone(), hash: *hash_num, is_mut}),
+                    Some(RecvKind::Borrow) => ResourceAccessPoint::StaticRef(StaticRef {name: func_argname.clone(), hash: *hash_num, is_mut}),
+                    Some(RecvKind::Move) => ResourceAccessPoint::Owner(Owner {name: func_argname.clone(), hash: *hash_num, is_mut}),
+                    None => {
+                        diag_insert(file, pat_type.span().start().line,
+                            format!("type {} of parameter {} not supported, no RAP created",
+                            pat_type.ty.to_token_stream(), func_argname), diagnostics);
+                        continue
+                    }
+                };
+                *hash_num+=1;
+                // T, &T, impl Trait: keep the trait bounds the argument is known by
+                let bounds = match type_elem(&pat_type.ty) {
+                    Type::ImplTrait(type_impl) => vec![bound_fmt(&type_impl.bounds)],
+                    Type::TraitObject(type_trait) => vec![bound_fmt(&type_trait.bounds)],
+                    elem => generics.get(&type_fmt(elem)).cloned().unwrap_or_default(),
+                };
+                if !bounds.is_empty() {
+                    arg_bounds.insert(func_argname, bounds);
+                }
+                args.push((pos, pat_ident.ident.clone(), arg_rap, type_fmt(&pat_type.ty)));
+            },
+        }
+    }
+    let lifetimes = sig.generics.lifetimes().map(|lifetime_def| format!("{}", lifetime_def.lifetime)).collect();
+    let ret_ref = match &sig.output {
+        syn::ReturnType::Type(_, ty) => match &**ty {
+            Type::Reference(type_reference) => Some((type_reference.mutability.is_some(),
+                type_reference.lifetime.as_ref().map(|lifetime| format!("{}", lifetime)))),
+            _ => None,
+        },
+        syn::ReturnType::Default => None,
+    };
+    let ret_ty = match &sig.output {
+        syn::ReturnType::Type(_, ty) => type_fmt(ty),
+        syn::ReturnType::Default => String::from("()"),
+    };
+    FnSig { func: func_rap, args, arg_bounds, lifetimes, ret_ref, ret_ty, is_async: sig.asyncness.is_some() }
+}
+
+fn arg_kind(ty: &syn::Type) -> Option<RecvKind> {
+    // how a parameter of this type holds its value:
+    // &T / &dyn Trait / &[T] -> Borrow, &mut T -> MutBorrow,
+    // T / impl Trait / (A, B) / [T; N] / fn(T) -> Move
+    match ty {
+        Type::Reference(type_reference) => {
+            if let Some(_mutability) = &type_reference.mutability {
+                Some(RecvKind::MutBorrow)
+            } else {
+                Some(RecvKind::Borrow)
+            }
+        },
+        Type::Paren(type_paren) => arg_kind(&type_paren.elem),
+        Type::Group(type_group) => arg_kind(&type_group.elem),
+        Type::Path(_) | Type::ImplTrait(_) | Type::TraitObject(_) | Type::Tuple(_)
+        | Type::Array(_) | Type::Slice(_) | Type::Ptr(_) | Type::BareFn(_)
+        | Type::Never(_) => Some(RecvKind::Move),
+        _ => None,
+    }
+}
+
+fn type_elem(ty: &syn::Type) -> &syn::Type {
+    // type behind references and parens: &mut (T) -> T
+    match ty {
+        Type::Reference(type_reference) => type_elem(&type_reference.elem),
+        Type::Paren(type_paren) => type_elem(&type_paren.elem),
+        Type::Group(type_group) => type_elem(&type_group.elem),
+        _ => ty,
+    }
+}
+
+fn generic_bounds(generics: &syn::Generics) -> HashMap<String, Vec<String>> {
+    // trait bounds of each type parameter, inline and from the where
+    // clause: <T: Display> ... where T: Clone -> T: [Display, Clone]
+    let mut bounds: HashMap<String, Vec<String>> = HashMap::new();
+    for type_param in generics.type_params() {
+        let entry = bounds.entry(format!("{}", type_param.ident)).or_default();
+        if !type_param.bounds.is_empty() {
+            entry.push(bound_fmt(&type_param.bounds));
+        }
+    }
+    if let Some(where_clause) = &generics.where_clause {
+        for predicate in where_clause.predicates.iter() {
+            if let syn::WherePredicate::Type(predicate_type) = predicate {
+                if let Some(entry) = bounds.get_mut(&type_fmt(&predicate_type.bounded_ty)) {
+                    entry.push(bound_fmt(&predicate_type.bounds));
                 }
-                // parse function block
-                for stmt in &func.block.stmts {
-                    parse_stmt(&stmt, data, hash_num, stack_num+1);
+            }
+        }
+    }
+    bounds
+}
+
+fn ref_lifetime(ty: &str) -> Option<String> {
+    // lifetime of a written reference type: &'a mut str -> 'a
+    if !ty.starts_with("&'") {
+        return None
+    }
+    ty[1..].split(' ').next().map(|lifetime| lifetime.to_string())
+}
+
+fn ret_sources(sig: &FnSig) -> Vec<usize> {
+    // positions of the parameters a returned reference may borrow from:
+    // those sharing its lifetime, or the only reference parameter when elided
+    let ref_params: Vec<&(usize, Ident, ResourceAccessPoint, String)> = sig.args.iter()
+        .filter(|(_, _, param, _)| matches!(param, ResourceAccessPoint::MutRef(_) | ResourceAccessPoint::StaticRef(_)))
+        .collect();
+    match &sig.ret_ref {
+        Some((_, Some(lifetime))) => ref_params.into_iter()
+            .filter(|(_, _, _, ty)| ref_lifetime(ty).as_ref() == Some(lifetime))
+            .map(|(pos, _, _, _)| *pos)
+            .collect(),
+        Some((_, None)) if ref_params.len() == 1 => vec![ref_params[0].0],
+        // elided with &self among several references: borrows from self
+        Some((_, None)) if ref_params.first().is_some_and(|(_, p_ident, _, _)| p_ident == "self") => vec![ref_params[0].0],
+        _ => Vec::new(),
+    }
+}
+
+fn ref_owners(arg: &syn::Expr, data: &data_pkg, stack_num: usize) -> Vec<Arc<ResourceAccessPoint>> {
+    // owners an argument borrows: &x -> x, r (a reference to x) -> x
+    match arg {
+        Expr::Reference(expred) => expr_base(&expred.expr)
+            .and_then(|name| rap_lookup(&name, data, stack_num))
+            .into_iter().collect(),
+        Expr::Paren(expr_paren) => ref_owners(&expr_paren.expr, data, stack_num),
+        _ => match expr_base(arg).and_then(|name| rap_lookup(&name, data, stack_num)) {
+            Some(rap) => match data.ref_targets.get(rap.hash()) {
+                Some(targets) => targets.clone(),
+                None => vec![rap],
+            },
+            None => Vec::new(),
+        },
+    }
+}
+
+fn fn_sig_collect(items: &Vec<syn::Item>,
+    data: &mut data_pkg,
+    hash_num: &mut u64) {
+    // register the signature of every function in the file before any
+    // body is parsed, so calls to functions defined further down the
+    // file can still be matched against their parameter list
+    for item in items {
+        match item {
+            Item::Fn(func) => {
+                let func_name = format!("{}()", mod_name_fmt(&data.mod_path.join("::"), &format!("{}", func.sig.ident)));
+                let mut sig = fn_sig_gen(&func.sig, func_name.clone(), hash_num, &data.cur_file, &mut data.diagnostics);
+                sig.ret_ty = ret_ty_resolve(&sig.ret_ty, None, data);
+                data.fn_sigs.insert(func_name, sig);
+            },
+            Item::Mod(itemmod) => {
+                if let Some((_, mod_items)) = &itemmod.content {
+                    data.mod_path.push(format!("{}", itemmod.ident));
+                    fn_sig_collect(mod_items, data, hash_num);
+                    data.mod_path.pop();
                 }
             },
-            Item::Struct(itemstruct) => {
-                // TODO: fix struct
-                let struct_type = format!("{}", itemstruct.ident);
-                match &itemstruct.fields {
-                    syn::Fields::Named(named_field) => {
-                        for i in &named_field.named {
-                            let struct_rap = ResourceAccessPoint::Struct(
-                                Struct {
-                                    name: format!("{}", i.ident.clone().unwrap()),
-                                    hash: hash_num.clone(),
-                                    owner: hash_num.clone(), // no owner for struct declaration
-                                    is_mut: false,
-                                    is_member: false,
-                                }
-                            );
-                            *hash_num+=1;
-                            struct_def_insert(Infoitem::Struct(i.clone()), struct_type.clone(), struct_rap, data, stack_num);
+            Item::Impl(itemimpl) => {
+                let self_ty = impl_self_fmt(&itemimpl.self_ty, data);
+                let trait_name = itemimpl.trait_.as_ref().map(|(_, path, _)| trait_resolve(path, data));
+                for impl_item in &itemimpl.items {
+                    if let syn::ImplItem::Method(method) = impl_item {
+                        if let Some(kind) = recv_kind_gen(&method.sig) {
+                            data.method_recv.insert(format!("{}::{}", self_ty, method.sig.ident), kind);
                         }
-                    },
-                    _ => {
-                        println!("not supported");
+                        let method_name = format!("{}", method.sig.ident);
+                        let mut sig = fn_sig_gen(&method.sig, format!("{}::{}()", self_ty, method_name), hash_num, &data.cur_file, &mut data.diagnostics);
+                        sig.ret_ty = ret_ty_resolve(&sig.ret_ty, Some(&self_ty), data);
+                        data.fn_sigs.insert(method_key(&self_ty, trait_name.as_ref(), &method_name), sig);
                     }
-                }      
+                }
             },
-            _ => info!("syn::Item option not supported")
+            Item::Trait(itemtrait) => {
+                let trait_name = mod_name_fmt(&data.mod_path.join("::"), &format!("{}", itemtrait.ident));
+                for trait_item in &itemtrait.items {
+                    if let syn::TraitItem::Method(method) = trait_item {
+                        let method_name = format!("{}", method.sig.ident);
+                        let sig = fn_sig_gen(&method.sig, format!("{}::{}()", trait_name, method_name), hash_num, &data.cur_file, &mut data.diagnostics);
+                        data.fn_sigs.insert(format!("{}::{}()", trait_name, method_name), sig);
+                    }
+                }
+            },
+            _ => {}
         }
     }
-    // var_def
 }
 
-// used to derive info from expr parse after eq sign
-#[derive(Debug)]
-struct expr_derive {
-    name: String,
-    var_mut: bool,
-    is_ref: bool,
-    ref_mut: bool,
-    is_struct: bool,
-    hash: u64,
+fn ret_ty_resolve(ret_ty: &str, self_ty: Option<&str>, data: &data_pkg) -> String {
+    // a returned struct is named in the module of the function, not of
+    // its callers: fn new() -> Self / -> Rect inside mod shapes -> shapes::Rect
+    let ret_ty = match self_ty {
+        Some(self_ty) => self_fmt(ret_ty, self_ty),
+        None => ret_ty.to_string(),
+    };
+    mod_resolve(&ret_ty, data, |name| data.struct_info.contains_key(name))
 }
 
-fn parse_stmt(stmt: &syn::Stmt, 
+fn struct_collect(items: &Vec<syn::Item>,
+    data: &mut data_pkg,
+    hash_num: &mut u64) {
+    // register struct definitions before impls and signatures are
+    // resolved, a struct may be declared below the code using it
+    for item in items {
+        match item {
+            Item::Struct(itemstruct) => struct_insert(itemstruct, data, hash_num, 0),
+            Item::Mod(itemmod) => {
+                if let Some((_, mod_items)) = &itemmod.content {
+                    data.mod_path.push(format!("{}", itemmod.ident));
+                    let outer_file = data.cur_file.clone();
+                    if let Some(mod_file) = data.mod_files.get(&data.mod_path.join("::")) {
+                        data.cur_file = mod_file.clone();
+                    }
+                    struct_collect(mod_items, data, hash_num);
+                    data.cur_file = outer_file;
+                    data.mod_path.pop();
+                }
+            },
+            _ => {}
+        }
+    }
+}
+
+fn struct_insert(itemstruct: &syn::ItemStruct,
     data: &mut data_pkg,
     hash_num: &mut u64,
     stack_num: usize) {
-    // local => let statement
-    // Item => function definition, struct definition etc.
-    // Expr => Expression without semicolon (return...)
-    // Semi => Expression with semicolon
-
-    match stmt {
-        Stmt::Local(loc) => {
-            let mut expr_pass = expr_derive {
+    // register a struct type: its fields in var_def and its shape in struct_info
+    let struct_type = mod_name_fmt(&data.mod_path.join("::"), &format!("{}", itemstruct.ident));
+    if data.struct_info.contains_key(&struct_type) {
+        return
+    }
+    let mut struct_def = StructDef {
+        kind: StructKind::Unit,
+        generics: generics_fmt(&itemstruct.generics),
+        fields: Vec::new(),
+    };
+    let fields: Vec<(String, &syn::Field)> = match &itemstruct.fields {
+        syn::Fields::Named(named_field) => {
+            struct_def.kind = StructKind::Named;
+            named_field.named.iter().map(|i| (format!("{}", i.ident.clone().unwrap()), i)).collect()
+        },
+        syn::Fields::Unnamed(unnamed_field) => {
+            // tuple struct, fields are named by position
+            struct_def.kind = StructKind::Tuple;
+            unnamed_field.unnamed.iter().enumerate().map(|(pos, i)| (format!("{}", pos), i)).collect()
+        },
+        syn::Fields::Unit => Vec::new(),
+    };
+    if !data.var_def.contains_key(&struct_type) {
+        // unit struct (or no fields): still a known struct type
+        data.var_def.insert(struct_type.clone(), HashMap::new());
+    }
+    for (field_name, i) in fields {
+        let struct_rap = ResourceAccessPoint::Struct(
+            Struct {
+                name: field_name.clone(),
+                hash: *hash_num,
+                owner: *hash_num, // no owner for struct declaration
+                is_mut: false,
+                is_member: false,
+            }
+        );
+        *hash_num+=1;
+        struct_def.fields.push((field_name, type_fmt(&i.ty)));
+        struct_def_insert(Infoitem::Struct(i.clone()), struct_type.clone(), struct_rap, data, stack_num);
+    }
+    debug!("struct found: {} {:?}", struct_type, struct_def);
+    data.struct_info.insert(struct_type, struct_def);
+}
+
+fn global_collect(items: &Vec<syn::Item>,
+    data: &mut data_pkg,
+    hash_num: &mut u64) {
+    // consts and statics are visible to every function of their module,
+    // wherever they are declared: register them before any body is parsed
+    for item in items {
+        match item {
+            Item::Const(_) | Item::Static(_) => global_insert(item, data, hash_num, 0),
+            Item::Mod(itemmod) => {
+                if let Some((_, mod_items)) = &itemmod.content {
+                    data.mod_path.push(format!("{}", itemmod.ident));
+                    let outer_file = data.cur_file.clone();
+                    if let Some(mod_file) = data.mod_files.get(&data.mod_path.join("::")) {
+                        data.cur_file = mod_file.clone();
+                    }
+                    global_collect(mod_items, data, hash_num);
+                    data.cur_file = outer_file;
+                    data.mod_path.pop();
+                }
+            },
+            _ => {}
+        }
+    }
+}
+
+fn global_insert(item: &syn::Item,
+    data: &mut data_pkg,
+    hash_num: &mut u64,
+    stack_num: usize) {
+    // allocate a const or static: an owner, or a static reference for
+    // `const NAME: &str = "..."`, that is never dropped
+    let (ident, ty, is_mut) = match item {
+        Item::Const(itemconst) => (&itemconst.ident, &itemconst.ty, false),
+        Item::Static(itemstatic) => (&itemstatic.ident, &itemstatic.ty, itemstatic.mutability.is_some()),
+        _ => return,
+    };
+    let global_name = mod_name_fmt(&data.mod_path.join("::"), &format!("{}", ident));
+    if data.globals.contains(&global_name) {
+        return
+    }
+    let global_rap = match &**ty {
+        Type::Reference(_) => ResourceAccessPoint::StaticRef(StaticRef {name: global_name.clone(), hash: *hash_num, is_mut}),
+        _ => ResourceAccessPoint::Owner(Owner {name: global_name.clone(), hash: *hash_num, is_mut}),
+    };
+    *hash_num+=1;
+    if let Some(global_arc) = var_allo_insert(Infoitem::Global(ident.clone()), global_rap, data, stack_num) {
+        let info = data.rap_info.entry(*global_arc.hash()).or_default();
+        info.is_static = true;
+        info.ty = type_fmt(ty);
+    }
+    data.globals.insert(global_name);
+}
+
+fn trait_collect(items: &Vec<syn::Item>, data: &mut data_pkg) {
+    // register trait definitions and which types implement them before
+    // signatures are collected, impls may come before their trait
+    for item in items {
+        match item {
+            Item::Trait(itemtrait) => {
+                let trait_name = mod_name_fmt(&data.mod_path.join("::"), &format!("{}", itemtrait.ident));
+                let methods = itemtrait.items.iter().filter_map(|trait_item| match trait_item {
+                    syn::TraitItem::Method(method) => Some(format!("{}", method.sig.ident)),
+                    _ => None,
+                }).collect();
+                data.traits.insert(trait_name, methods);
+            },
+            Item::Mod(itemmod) => {
+                if let Some((_, mod_items)) = &itemmod.content {
+                    data.mod_path.push(format!("{}", itemmod.ident));
+                    trait_collect(mod_items, data);
+                    data.mod_path.pop();
+                }
+            },
+            _ => {}
+        }
+    }
+    trait_impl_collect(items, data);
+}
+
+fn trait_impl_collect(items: &Vec<syn::Item>, data: &mut data_pkg) {
+    // impl Summary for Post -> trait_impls[Post] = [Summary]
+    for item in items {
+        match item {
+            Item::Impl(itemimpl) => {
+                if let Some((_, path, _)) = &itemimpl.trait_ {
+                    let self_ty = impl_self_fmt(&itemimpl.self_ty, data);
+                    let trait_name = trait_resolve(path, data);
+                    data.trait_impls.entry(self_ty).or_default().push(trait_name);
+                }
+            },
+            Item::Mod(itemmod) => {
+                if let Some((_, mod_items)) = &itemmod.content {
+                    data.mod_path.push(format!("{}", itemmod.ident));
+                    trait_impl_collect(mod_items, data);
+                    data.mod_path.pop();
+                }
+            },
+            _ => {}
+        }
+    }
+}
+
+fn impl_self_fmt(self_ty: &syn::Type, data: &data_pkg) -> String {
+    // module qualified name of an impl's self type: impl<T> Wrapper<T> -> shapes::Wrapper
+    match type_elem(self_ty) {
+        Type::Path(type_path) => {
+            let path: Vec<String> = type_path.path.segments.iter().map(|seg| format!("{}", seg.ident)).collect();
+            mod_resolve(&path.join("::"), data, |name| data.var_def.contains_key(name) || data.struct_info.contains_key(name))
+        },
+        ty => type_fmt(ty),
+    }
+}
+
+fn trait_resolve(path: &syn::Path, data: &data_pkg) -> String {
+    // module qualified name of a trait; std traits keep their written path
+    mod_resolve(&syn_path_fmt(path), data, |name| data.traits.contains_key(name))
+}
+
+fn self_fmt(ty: &str, self_ty: &str) -> String {
+    // replace Self in a written type inside an impl: &mut Self -> &mut Point
+    let mut out = String::new();
+    let mut rest = ty;
+    while let Some(pos) = rest.find("Self") {
+        let before = rest[..pos].chars().last();
+        let after = rest[pos+4..].chars().next();
+        let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+        out.push_str(&rest[..pos]);
+        if is_word(before) || is_word(after) {
+            out.push_str("Self");
+        } else {
+            out.push_str(self_ty);
+        }
+        rest = &rest[pos+4..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn self_path(path: &str, data: &data_pkg) -> String {
+    // Self::new inside impl Rect -> Rect::new
+    match (&data.cur_self, path.strip_prefix("Self::")) {
+        (Some(self_ty), Some(rest)) => format!("{}::{}", self_ty, rest),
+        _ => path.to_string(),
+    }
+}
+
+fn call_graph_insert(callee: &String, data: &mut data_pkg) {
+    // edge from the function being parsed to a function of the file
+    if let Some(caller) = data.cur_fn.clone() {
+        let callees = data.call_graph.entry(caller).or_default();
+        if !callees.contains(callee) {
+            callees.push(callee.clone());
+        }
+    }
+}
+
+fn method_key(self_ty: &String, trait_name: Option<&String>, method: &String) -> String {
+    // fn_sigs key of a method: Point::area() for inherent methods,
+    // <Point as Display>::fmt() for trait impl methods
+    match trait_name {
+        Some(trait_name) => format!("<{} as {}>::{}()", self_ty, trait_name, method),
+        None => format!("{}::{}()", self_ty, method),
+    }
+}
+
+fn method_resolve(method: &String, recv: Option<&Arc<ResourceAccessPoint>>, data: &data_pkg) -> Option<String> {
+    // the definition a method call dispatches to: an inherent method of
+    // the receiver type, then its trait impls, then trait default
+    // methods; receivers of generic type go through their trait bounds.
+    // Without a known type a method name defined only once is taken
+    let info = recv.and_then(|recv| data.rap_info.get(recv.hash()));
+    let ty = type_name(&info.map(|info| info.ty.clone()).unwrap_or_default());
+    let mut candidates = Vec::new();
+    if !ty.is_empty() {
+        candidates.push(method_key(&ty, None, method));
+        if let Some(traits) = data.trait_impls.get(&ty) {
+            for trait_name in traits {
+                candidates.push(method_key(&ty, Some(trait_name), method));
+                candidates.push(format!("{}::{}()", trait_name, method));
+            }
+        }
+    }
+    if let Some(info) = info {
+        for bound in info.bounds.iter() {
+            for trait_name in bound.split(" + ") {
+                candidates.push(format!("{}::{}()", trait_name, method));
+            }
+        }
+    }
+    if let Some(key) = candidates.into_iter().find(|key| data.fn_sigs.contains_key(key)) {
+        return Some(key)
+    }
+    if !ty.is_empty() {
+        return None
+    }
+    let suffix = format!("::{}()", method);
+    let mut matches: Vec<&String> = data.fn_sigs.keys().filter(|key| key.ends_with(&suffix)).collect();
+    matches.sort();
+    // inherent methods first: Point::area() before <Point as Shape>::area()
+    let inherent: Vec<&&String> = matches.iter().filter(|key| !key.starts_with('<')).collect();
+    match (inherent.len(), matches.len()) {
+        (1, _) => Some(inherent[0].to_string()),
+        (0, 1) => Some(matches[0].clone()),
+        _ => None,
+    }
+}
+
+fn assoc_resolve(path: &str, data: &data_pkg) -> (String, Option<String>) {
+    // FnSigs key of a path call and the type whose impl defines it:
+    // Rect::new() -> (Rect::new(), Some(Rect)), Point::default() found in
+    // impl Default for Point -> (<Point as Default>::default(), Some(Point)),
+    // geo::helper() -> (geo::helper(), None)
+    let path = self_path(path, data);
+    let local = mod_resolve(&path, data, |name| data.fn_sigs.contains_key(&format!("{}()", name)));
+    let (type_path, fn_name) = match local.rfind("::") {
+        Some(pos) => (local[..pos].to_string(), local[pos+2..].to_string()),
+        None => return (format!("{}()", local), None),
+    };
+    let self_ty = mod_resolve(&type_path, data, |name| data.struct_info.contains_key(name));
+    if !data.struct_info.contains_key(&self_ty) {
+        return (format!("{}()", local), None)
+    }
+    let key = format!("{}()", local);
+    if data.fn_sigs.contains_key(&key) {
+        return (key, Some(self_ty))
+    }
+    let trait_key = data.trait_impls.get(&self_ty).and_then(|traits| traits.iter()
+        .map(|trait_name| method_key(&self_ty, Some(trait_name), &fn_name))
+        .find(|key| data.fn_sigs.contains_key(key)));
+    (trait_key.unwrap_or(key), Some(self_ty))
+}
+
+fn assoc_ret_struct(sig: &FnSig, self_ty: &str, data: &data_pkg) -> Option<String> {
+    // struct an associated function returns by value:
+    // fn new() -> Self / fn make() -> Rect inside impl Rect -> Rect
+    let ret_ty = self_fmt(&sig.ret_ty, self_ty);
+    let struct_type = mod_resolve(&ret_ty, data, |name| data.var_def.contains_key(name));
+    if data.var_def.contains_key(&struct_type) { Some(struct_type) } else { None }
+}
+
+fn recv_kind_gen(sig: &syn::Signature) -> Option<RecvKind> {
+    // read the receiver of a method signature:
+    // &self / &mut self / self, also the typed self: &Self forms
+    match sig.inputs.first() {
+        Some(FnArg::Receiver(receiver)) => {
+            match &receiver.reference {
+                Some(_) => {
+                    if let Some(_mutability) = &receiver.mutability {
+                        Some(RecvKind::MutBorrow)
+                    } else {
+                        Some(RecvKind::Borrow)
+                    }
+                },
+                None => Some(RecvKind::Move),
+            }
+        },
+        Some(FnArg::Typed(pat_type)) => {
+            match &*pat_type.pat {
+                Pat::Ident(pat_ident) if pat_ident.ident == "self" => {
+                    match &*pat_type.ty {
+                        Type::Reference(type_reference) => {
+                            if let Some(_mutability) = &type_reference.mutability {
+                                Some(RecvKind::MutBorrow)
+                            } else {
+                                Some(RecvKind::Borrow)
+                            }
+                        },
+                        _ => Some(RecvKind::Move),
+                    }
+                },
+                _ => None, // associated function, no receiver
+            }
+        },
+        None => None,
+    }
+}
+
+fn std_recv_kind(method: &str) -> Option<RecvKind> {
+    // receiver kind of common std methods used in the examples
+    match method {
+        "len" | "is_empty" | "iter" | "get" | "first" | "last" | "contains"
+        | "as_str" | "as_bytes" | "chars" | "bytes" | "clone" | "to_string"
+        | "to_owned" | "to_uppercase" | "to_lowercase" | "trim" | "split"
+        | "lines" | "starts_with" | "ends_with" | "find" | "capacity"
+        | "keys" | "values" | "contains_key" | "eq" | "cmp" | "as_ref"
+        | "is_some" | "is_none" | "is_ok" | "is_err" | "borrow"
+        | "borrow_mut" | "get_ref" => Some(RecvKind::Borrow),
+        "push" | "push_str" | "pop" | "insert" | "remove" | "clear" | "sort"
+        | "sort_by" | "dedup" | "truncate" | "extend" | "append" | "drain"
+        | "iter_mut" | "get_mut" | "first_mut" | "last_mut" | "retain"
+        | "reverse" | "swap" | "entry" | "as_mut" | "take" => Some(RecvKind::MutBorrow),
+        "into_bytes" | "into_iter" | "into_boxed_str" | "into_string" | "unwrap"
+        | "expect" | "unwrap_or" | "unwrap_or_default" | "ok" | "err" 
+        | "into" => Some(RecvKind::Move),
+        _ => None,
+    }
+}
+
+fn type_name(ty: &str) -> String {
+    // type name without references and generic arguments:
+    // &mut Vec<i32> -> Vec
+    let mut ty = ty.trim_start_matches('&');
+    if ty.starts_with('\'') {
+        ty = ty.split_once(' ').map(|(_, rest)| rest).unwrap_or("");
+    }
+    let ty = ty.trim_start_matches("mut ");
+    match ty.find('<') {
+        Some(pos) => ty[..pos].to_string(),
+        None => ty.to_string(),
+    }
+}
+
+fn ref_base(expr: &syn::Expr) -> Option<String> {
+    // variable borrowed by &a / &mut a, or a itself
+    match expr {
+        Expr::Reference(expred) => expr_base(&expred.expr),
+        _ => expr_base(expr),
+    }
+}
+
+fn expr_base(expr: &syn::Expr) -> Option<String> {
+    // name of the variable an expression place is rooted at:
+    // s -> s, s.name -> s, (s) -> s, v[0] -> v
+    match expr {
+        Expr::Path(exprpath) => Some(path_fmt(exprpath)),
+        Expr::Field(expr_field) => expr_base(&expr_field.base),
+        Expr::Paren(expr_paren) => expr_base(&expr_paren.expr),
+        Expr::Index(expr_index) => expr_base(&expr_index.expr),
+        _ => None,
+    }
+}
+
+fn local_init(pending: PendingLocal,
+    value: &syn::Expr,
+    line: usize,
+    data: &mut data_pkg,
+    hash_num: &mut u64,
+    stack_num: usize) {
+    // first assignment of a let without value: derive the binding from
+    // the assigned value as a let initializer would, and declare it where
+    // the let was, keeping its drop order
+    let mut expr_pass = pending.derive;
+    parse_expr(value, Some(&mut expr_pass), data, hash_num, stack_num);
+    init_infer(value, &mut expr_pass, data, stack_num);
+    if let Some(expr_arc) = local_insert(&pending.pat, expr_pass, line, data, pending.stack_num) {
+        if stack_num > pending.stack_num {
+            data.late_init.insert(*expr_arc.hash());
+        }
+        if let Some(frame) = data.scopes.get_mut(pending.scope) {
+            let pos = std::cmp::min(pending.pos, frame.decls.len());
+            frame.decls.insert(pos, expr_arc);
+        }
+    }
+}
+
+fn deref_parse(expr_unary: &syn::ExprUnary,
+    data: &mut data_pkg,
+    hash_num: &mut u64,
+    stack_num: usize) {
+    // *r, **r, *r.field: peel the derefs down to the reference variable,
+    // then follow the chain of references it was borrowed through,
+    // highlighting every reference on it and the owner at its end
+    let mut depth = 1;
+    let mut inner = &*expr_unary.expr;
+    loop {
+        match inner {
+            Expr::Unary(inner_unary) if matches!(inner_unary.op, syn::UnOp::Deref(_)) => {
+                depth += 1;
+                inner = &*inner_unary.expr;
+            },
+            Expr::Paren(expr_paren) => inner = &*expr_paren.expr,
+            _ => break,
+        }
+    }
+    let base = match inner {
+        Expr::Path(exprpath) => Some(path_fmt(exprpath)),
+        Expr::Field(_) | Expr::Index(_) => expr_base(inner),
+        _ => None,
+    };
+    let base = match base {
+        Some(base) => base,
+        None => {
+            // *v.get_mut(0).unwrap(): nothing named to dereference
+            parse_expr(inner, None, data, hash_num, stack_num);
+            return
+        }
+    };
+    debug!(" Ref target: {}", base);
+    if let Expr::Index(expr_index) = inner {
+        index_parse(expr_index, data, hash_num, stack_num);
+    }
+    let ref_rap = match non_allo_insert(base, Infoitem::Dereference(expr_unary.clone()),
+        None, data, hash_num, stack_num) {
+        Some(rap) => rap,
+        None => return,
+    };
+    let line = expr_unary.span().start().line;
+    let mut chain = vec![ref_rap];
+    for _ in 0..depth {
+        let next: Vec<Arc<ResourceAccessPoint>> = chain.iter()
+            .filter_map(|rap| data.ref_targets.get(rap.hash()))
+            .flatten()
+            .cloned()
+            .collect();
+        if next.is_empty() {
+            break
+        }
+        for rap in next.iter() {
+            color_push(rap, Infoitem::Dereference(expr_unary.clone()), line, data, stack_num);
+        }
+        chain = next;
+    }
+}
+
+fn reborrow_base(expr: &syn::Expr) -> Option<String> {
+    // variable under the derefs / inner borrows of a reborrow:
+    // *r1 -> r1, **r -> r, &x (from &&x) -> x
+    match expr {
+        Expr::Unary(expr_unary) if matches!(expr_unary.op, syn::UnOp::Deref(_)) => {
+            match &*expr_unary.expr {
+                Expr::Path(exprpath) => Some(path_fmt(exprpath)),
+                inner => reborrow_base(inner),
+            }
+        },
+        Expr::Reference(expred) => match &*expred.expr {
+            Expr::Path(exprpath) => Some(path_fmt(exprpath)),
+            inner => reborrow_base(inner),
+        },
+        Expr::Paren(expr_paren) => reborrow_base(&expr_paren.expr),
+        _ => None,
+    }
+}
+
+fn ref_owner_chain(ref_rap: &Arc<ResourceAccessPoint>, data: &data_pkg) -> Vec<Arc<ResourceAccessPoint>> {
+    // owners a reference ultimately points to, following references
+    // to references: r2 = &r1, r1 = &x -> [x]
+    let mut owners = Vec::new();
+    let mut pending = vec![ref_rap.clone()];
+    let mut seen = HashSet::new();
+    while let Some(rap) = pending.pop() {
+        if !seen.insert(*rap.hash()) {
+            continue
+        }
+        match data.ref_targets.get(rap.hash()) {
+            Some(targets) => pending.extend(targets.iter().cloned()),
+            None if rap.hash() != ref_rap.hash() => owners.push(rap),
+            None => {},
+        }
+    }
+    owners
+}
+
+fn color_push(rap_arc: &Arc<ResourceAccessPoint>,
+    syn_info: Infoitem,
+    line: usize,
+    data: &mut data_pkg,
+    stack_num: usize) {
+    // an extra occurrence of an already allocated RAP, e.g. the owner
+    // reached through a dereference
+    let last = data.last_use.entry(*rap_arc.hash()).or_insert(line);
+    if *last < line {
+        *last = line;
+    }
+    let stack_item = StackItem {
+        SynInfo: syn_info,
+        ItemOrig: Arc::clone(rap_arc),
+        SrcFile: data.cur_file.clone(),
+    };
+    data.color_info[stack_num].entry(rap_arc.name().clone()).or_default().push(stack_item);
+}
+
+fn place_write(place: &syn::Expr,
+    line: usize,
+    data: &mut data_pkg,
+    stack_num: usize) {
+    // record an assignment to a place as a mutation point of the RAPs
+    // it writes: x = .., p.x = .., v[i] = .. mutate x / p / v,
+    // *r = .. mutates r and the owner r borrows; flag places whose
+    // binding does not allow the write
+    let root = match place {
+        Expr::Unary(expr_unary) => match expr_unary.op {
+            syn::UnOp::Deref(_) => expr_base(&expr_unary.expr),
+            _ => None,
+        },
+        Expr::Paren(expr_paren) => return place_write(&expr_paren.expr, line, data, stack_num),
+        _ => expr_base(place),
+    };
+    let root_rap = match root.and_then(|name| rap_lookup(&name, data, stack_num)) {
+        Some(rap) => rap,
+        None => return,
+    };
+    let whole = matches!(place, Expr::Path(_));
+    if whole && data.late_init.contains(root_rap.hash()) {
+        // the other branch initializing a let without value
+        return
+    }
+    let raw = data.rap_info.get(root_rap.hash()).and_then(|info| info.pointer.first().cloned());
+    let writable = match &*root_rap {
+        // r = &y rebinds r, r.x = .. / *r = .. write through it
+        ResourceAccessPoint::MutRef(mut_ref) => !whole || mut_ref.is_mut,
+        ResourceAccessPoint::StaticRef(static_ref) => whole && static_ref.is_mut,
+        // *p = .. writes through a *mut pointer
+        ResourceAccessPoint::Owner(owner) => owner.is_mut || (!whole && raw == Some(PtrKind::RawMut)),
+        ResourceAccessPoint::Struct(struct_rap) => struct_rap.is_mut,
+        ResourceAccessPoint::Function(_) => false,
+    };
+    let place_str = place.to_token_stream().to_string().replace(' ', "");
+    if !writable {
+        diag_insert(&data.cur_file, line, format!("assignment to {} which is not declared mutable", place_str), &mut data.diagnostics);
+    }
+    let mut written = vec![root_rap.clone()];
+    if let Expr::Field(_) = place {
+        // p.x = ..: the member too
+        if let Some(member) = data.members.get(&place_str) {
+            written.push(member.clone());
+        }
+    }
+    if !whole {
+        // writes through a reference land in the owners it borrows
+        if let Some(targets) = data.ref_targets.get(root_rap.hash()) {
+            written.extend(targets.iter().cloned());
+        }
+    }
+    let pointees: Vec<u64> = match (&raw, data.rap_info.get(root_rap.hash())) {
+        (Some(PtrKind::RawConst), Some(info)) | (Some(PtrKind::RawMut), Some(info)) if !whole => info.points_to.clone(),
+        _ => Vec::new(),
+    };
+    for hash in written.iter().map(|rap| *rap.hash()).chain(pointees) {
+        let mutations = &mut data.rap_info.entry(hash).or_default().mutations;
+        if !mutations.contains(&line) {
+            mutations.push(line);
+        }
+    }
+}
+
+fn block_parse(block: &syn::Block,
+    stmt_pass: Option<&mut expr_derive>,
+    data: &mut data_pkg,
+    hash_num: &mut u64,
+    stack_num: usize) {
+    // a nested block gets its own stack level and scope frame,
+    // the value of the block is its tail expression
+    data.color_info.push(HashMap::new());
+    scope_enter(block, data);
+    match (stmt_pass, block.stmts.split_last()) {
+        (Some(stmt_derive), Some((Stmt::Expr(tail), stmts))) => {
+            for stmt in stmts {
+                parse_stmt(stmt, data, hash_num, stack_num+1);
+            }
+            parse_expr(tail, Some(stmt_derive), data, hash_num, stack_num+1);
+        },
+        _ => {
+            for stmt in &block.stmts {
+                parse_stmt(stmt, data, hash_num, stack_num+1);
+            }
+        }
+    }
+    scope_exit(data);
+}
+
+fn exit_enter(label: Option<&syn::Label>, is_loop: bool, bound: bool, data: &mut data_pkg) {
+    // a loop or labelled block whose body opens the next scope frame
+    data.loop_exits.push(LoopExit {
+        label: label.map(|label| format!("{}", label.name)),
+        is_loop,
+        scope: data.scopes.len(),
+        bound,
+        binds: Vec::new(),
+    });
+}
+
+fn exit_scope_parse(block: &syn::Block,
+    label: Option<&syn::Label>,
+    is_loop: bool,
+    stmt_pass: Option<&mut expr_derive>,
+    data: &mut data_pkg,
+    hash_num: &mut u64,
+    stack_num: usize) {
+    // a loop or labelled block: let v = loop { .. break c; }; moves c
+    // into v, the block's own scope does not drop it
+    exit_enter(label, is_loop, stmt_pass.is_some(), data);
+    block_parse(block, None, data, hash_num, stack_num);
+    if let (Some(exit), Some(stmt_derive)) = (data.loop_exits.pop(), stmt_pass) {
+        stmt_derive.binds.extend(exit.binds);
+    }
+}
+
+fn pat_bind(pat: &Pat,
+    data: &mut data_pkg,
+    hash_num: &mut u64,
+    stack_num: usize) {
+    // allocate the variables a pattern binds in the innermost scope:
+    // Some(x), (a, mut b), Point { x, y: ref py }, n @ 1..=5
+    let mut collector = PatCollector { idents: Vec::new() };
+    collector.visit_pat(pat);
+    for pat_ident in collector.idents {
+        let name = format!("{}", pat_ident.ident);
+        if name.starts_with(|c: char| c.is_uppercase()) || name == "_" {
+            // None, MAX: unit variants and consts, not bindings
+            continue
+        }
+        let is_mut = pat_ident.mutability.is_some();
+        let bind_rap = match &pat_ident.by_ref {
+            Some(_) if is_mut => ResourceAccessPoint::MutRef(MutRef {name, hash: *hash_num, is_mut: false}),
+            Some(_) => ResourceAccessPoint::StaticRef(StaticRef {name, hash: *hash_num, is_mut: false}),
+            None => ResourceAccessPoint::Owner(Owner {name, hash: *hash_num, is_mut}),
+        };
+        *hash_num+=1;
+        let is_ref = pat_ident.by_ref.is_some();
+        if let Some(bind_arc) = var_allo_insert(Infoitem::Local(pat_ident), bind_rap, data, stack_num) {
+            scope_decl(&bind_arc, data);
+            if is_ref {
+                data.fn_refs.push(bind_arc);
+            }
+        }
+    }
+}
+
+struct PatCollector {
+    ///
+    /// identifiers bound anywhere inside a pattern
+    /// 
+    idents: Vec<syn::PatIdent>,
+}
+
+struct PathCollector {
+    ///
+    /// single segment paths (variable names) used inside an expression,
+    /// in order of first use
+    /// 
+    names: Vec<String>,
+}
+
+impl<'ast> Visit<'ast> for PathCollector {
+    fn visit_expr_path(&mut self, expr_path: &'ast syn::ExprPath) {
+        if expr_path.qself.is_none() && expr_path.path.segments.len() == 1 {
+            let name = path_fmt(expr_path);
+            if !self.names.contains(&name) {
+                self.names.push(name);
+            }
+        }
+    }
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        // println!("{}", s), vec![a, b]: arguments that parse as expressions
+        let parser = syn::punctuated::Punctuated::<Expr, syn::Token![,]>::parse_terminated;
+        if let Ok(args) = mac.parse_body_with(parser) {
+            for arg in args.iter() {
+                self.visit_expr(arg);
+            }
+        }
+    }
+
+    fn visit_item(&mut self, _item: &'ast syn::Item) {
+        // nested items cannot capture locals
+    }
+}
+
+impl<'ast> Visit<'ast> for PatCollector {
+    fn visit_pat_ident(&mut self, pat_ident: &'ast syn::PatIdent) {
+        self.idents.push(pat_ident.clone());
+        if let Some((_, subpat)) = &pat_ident.subpat {
+            self.visit_pat(subpat);
+        }
+    }
+}
+
+struct ExprWalker<'a> {
+    ///
+    /// exhaustive traversal of the expressions parse_expr() has no
+    /// handler for: every sub-expression goes back through parse_expr(),
+    /// blocks through parse_stmt(), and pattern bindings of if let,
+    /// while let, match, for and closures get a scope frame of their own
+    /// 
+    data: &'a mut data_pkg,
+    hash_num: &'a mut u64,
+    stack_num: usize,
+}
+
+impl<'a> ExprWalker<'a> {
+    fn bind_scope(&mut self, pat: &Pat, end_line: usize) {
+        // open a frame closing at end_line holding the pattern's bindings
+        self.data.color_info.push(HashMap::new());
+        self.data.scopes.push(ScopeFrame {
+            end_line,
+            decls: Vec::new(),
+            is_fn: false,
+        });
+        pat_bind(pat, self.data, self.hash_num, self.stack_num+1);
+    }
+
+    fn bind_body(&mut self, block: &syn::Block) {
+        // statements of a block whose frame bind_scope() opened
+        for stmt in &block.stmts {
+            parse_stmt(stmt, self.data, self.hash_num, self.stack_num+1);
+        }
+        scope_exit(self.data);
+    }
+}
+
+impl<'ast, 'a> Visit<'ast> for ExprWalker<'a> {
+    fn visit_expr(&mut self, expr: &'ast syn::Expr) {
+        parse_expr(expr, None, self.data, self.hash_num, self.stack_num);
+    }
+
+    fn visit_block(&mut self, block: &'ast syn::Block) {
+        block_parse(block, None, self.data, self.hash_num, self.stack_num);
+    }
+
+    fn visit_expr_if(&mut self, expr_if: &'ast syn::ExprIf) {
+        match &*expr_if.cond {
+            Expr::Let(expr_let) => {
+                // if let Some(x) = opt { .. }: x lives in the then branch
+                self.visit_expr(&expr_let.expr);
+                self.bind_scope(&expr_let.pat, expr_if.then_branch.brace_token.span.end().line);
+                self.bind_body(&expr_if.then_branch);
+            },
+            cond => {
+                self.visit_expr(cond);
+                self.visit_block(&expr_if.then_branch);
+            }
+        }
+        if let Some((_, else_branch)) = &expr_if.else_branch {
+            self.visit_expr(else_branch);
+        }
+    }
+
+    fn visit_expr_while(&mut self, expr_while: &'ast syn::ExprWhile) {
+        match &*expr_while.cond {
+            Expr::Let(expr_let) => {
+                self.visit_expr(&expr_let.expr);
+                exit_enter(expr_while.label.as_ref(), true, false, self.data);
+                self.bind_scope(&expr_let.pat, expr_while.body.brace_token.span.end().line);
+                self.bind_body(&expr_while.body);
+                self.data.loop_exits.pop();
+            },
+            cond => {
+                self.visit_expr(cond);
+                exit_scope_parse(&expr_while.body, expr_while.label.as_ref(), true, None,
+                    self.data, self.hash_num, self.stack_num);
+            }
+        }
+    }
+
+    fn visit_expr_for_loop(&mut self, expr_for: &'ast syn::ExprForLoop) {
+        // for x in v { .. }: x lives in the loop body
+        self.visit_expr(&expr_for.expr);
+        exit_enter(expr_for.label.as_ref(), true, false, self.data);
+        self.bind_scope(&expr_for.pat, expr_for.body.brace_token.span.end().line);
+        self.bind_body(&expr_for.body);
+        self.data.loop_exits.pop();
+    }
+
+    fn visit_arm(&mut self, arm: &'ast syn::Arm) {
+        // Some(x) => ..: x lives until the end of the arm
+        self.bind_scope(&arm.pat, arm.span().end().line);
+        if let Some((_, guard)) = &arm.guard {
+            parse_expr(guard, None, self.data, self.hash_num, self.stack_num+1);
+        }
+        parse_expr(&arm.body, None, self.data, self.hash_num, self.stack_num+1);
+        scope_exit(self.data);
+    }
+
+    fn visit_expr_closure(&mut self, expr_closure: &'ast syn::ExprClosure) {
+        // |a, b| ..: parameters live until the end of the closure
+        self.data.color_info.push(HashMap::new());
+        self.data.scopes.push(ScopeFrame {
+            end_line: expr_closure.span().end().line,
+            decls: Vec::new(),
+            is_fn: false,
+        });
+        for input in expr_closure.inputs.iter() {
+            pat_bind(input, self.data, self.hash_num, self.stack_num+1);
+        }
+        parse_expr(&expr_closure.body, None, self.data, self.hash_num, self.stack_num+1);
+        scope_exit(self.data);
+    }
+
+    fn visit_item(&mut self, _item: &'ast syn::Item) {
+        // items inside expressions are reached through parse_stmt()
+    }
+}
+
+fn capture_insert(captures: Vec<String>,
+    is_move: bool,
+    line: usize,
+    stmt_pass: Option<&mut expr_derive>,
+    data: &mut data_pkg,
+    stack_num: usize) {
+    // variables of the environment a closure or async block uses: recorded
+    // on the let it is bound to, a move closure / async move block moves
+    // (or copies) them into that binding
+    let mut captured = Vec::new();
+    for name in captures {
+        if let Some(rap) = rap_lookup(&name, data, stack_num) {
+            if let ResourceAccessPoint::Function(_) = &*rap {
+                continue
+            }
+            let info = data.rap_info.get(rap.hash());
+            if info.is_some_and(|info| info.is_static) {
+                continue
+            }
+            let is_copy = info.is_some_and(|info| type_is_copy(&info.ty));
+            captured.push((rap, is_copy));
+        }
+    }
+    if let Some(stmt_derive) = stmt_pass {
+        data.rap_info.entry(stmt_derive.hash).or_default().captures = captured.iter()
+            .map(|(rap, _)| *rap.hash()).collect();
+        if is_move {
+            for (rap, is_copy) in captured {
+                let kind = if is_copy { BindEvent::Copy } else { BindEvent::Move };
+                stmt_derive.binds.push((line, kind, Some(rap)));
+            }
+        }
+    }
+}
+
+fn closure_captures(expr_closure: &syn::ExprClosure) -> Vec<String> {
+    // names a closure body uses that are not its own parameters
+    let mut params = PatCollector { idents: Vec::new() };
+    for input in expr_closure.inputs.iter() {
+        params.visit_pat(input);
+    }
+    let mut paths = PathCollector { names: Vec::new() };
+    paths.visit_expr(&expr_closure.body);
+    paths.names.into_iter()
+        .filter(|name| !params.idents.iter().any(|param| param.ident == name))
+        .collect()
+}
+
+fn async_block_parse(expr_async: &syn::ExprAsync,
+    data: &mut data_pkg,
+    hash_num: &mut u64,
+    stack_num: usize) {
+    // async { .. }: a block of its own that can be suspended at
+    // every .await inside it
+    data.color_info.push(HashMap::new());
+    scope_enter(&expr_async.block, data);
+    let outer_async = data.async_scope.replace(data.scopes.len()-1);
+    for stmt in &expr_async.block.stmts {
+        parse_stmt(stmt, data, hash_num, stack_num+1);
+    }
+    scope_exit(data);
+    data.async_scope = outer_async;
+}
+
+fn await_insert(line: usize, data: &mut data_pkg) {
+    // everything declared so far in the enclosing async body and still
+    // in scope is alive across the .await on line
+    let start = match data.async_scope {
+        Some(start) => start,
+        None => return,
+    };
+    let live: Vec<u64> = data.scopes.iter().skip(start)
+        .flat_map(|frame| frame.decls.iter().map(|rap| *rap.hash()))
+        .filter(|hash| !data.moved.contains(hash))
+        .collect();
+    for hash in live {
+        let awaits = &mut data.rap_info.entry(hash).or_default().awaits;
+        if !awaits.contains(&line) {
+            awaits.push(line);
+        }
+    }
+}
+
+fn raw_macro(expr_macro: &syn::ExprMacro) -> Option<bool> {
+    // addr_of!() -> Some(false), addr_of_mut!() -> Some(true)
+    match expr_macro.mac.path.segments.last().map(|seg| format!("{}", seg.ident)).as_deref() {
+        Some("addr_of") => Some(false),
+        Some("addr_of_mut") => Some(true),
+        _ => None,
+    }
+}
+
+fn raw_ptr_insert(place: &syn::Expr,
+    raw_mut: bool,
+    line: usize,
+    stmt_pass: Option<&mut expr_derive>,
+    data: &mut data_pkg,
+    hash_num: &mut u64,
+    stack_num: usize) {
+    // a raw pointer to place: a use of place, no borrow is tracked;
+    // the let it is bound to records the pointer and its pointee
+    parse_expr(place, None, data, hash_num, stack_num);
+    let target = expr_base(place).and_then(|name| rap_lookup(&name, data, stack_num));
+    if let Some(stmt_derive) = stmt_pass {
+        stmt_derive.pointer = vec![if raw_mut { PtrKind::RawMut } else { PtrKind::RawConst }];
+        if let Some(target) = target {
+            data.rap_info.entry(stmt_derive.hash).or_default().points_to = vec![*target.hash()];
+            stmt_derive.binds.push((line, BindEvent::RawPointer, Some(target)));
+        }
+    }
+}
+
+fn index_parse(expr_index: &syn::ExprIndex,
+    data: &mut data_pkg,
+    hash_num: &mut u64,
+    stack_num: usize) {
+    // variables used inside the brackets of v[i][j..k], innermost first
+    if let Expr::Index(inner) = &*expr_index.expr {
+        index_parse(inner, data, hash_num, stack_num);
+    }
+    parse_expr(&expr_index.index, None, data, hash_num, stack_num);
+}
+
+fn fn_item_parse(func: &syn::ItemFn,
+    func_name: String,
+    data: &mut data_pkg,
+    hash_num: &mut u64,
+    stack_num: usize) {
+    // parse a function or method definition registered in fn_sigs under
+    // func_name: allocate it and its arguments, then parse its body
+    let sig = match data.fn_sigs.get(&func_name) {
+        Some(sig) => sig.clone(),
+        None => {
+            // nested function, not seen by fn_sig_collect()
+            let sig = fn_sig_gen(&func.sig, func_name.clone(), hash_num, &data.cur_file, &mut data.diagnostics);
+            data.fn_sigs.insert(func_name.clone(), sig.clone());
+            sig
+        }
+    };
+    debug!("--------------");
+    debug!("func found: {:?}", sig.func);
+    debug!("{:?}", func.span().start());
+    debug!("{:?}", func.span().end());
+    debug!("--------------");
+    let outer_fn = data.cur_fn.replace(sig.func.name().clone());
+    // push stack and register func into color_info
+    data.color_info.push(HashMap::new());
+    if let Some(func_arc) = var_allo_insert(Infoitem::Func(func.clone()), 
+        sig.func, data, stack_num) {
+        if !sig.lifetimes.is_empty() {
+            data.rap_info.entry(*func_arc.hash()).or_default().lifetimes = sig.lifetimes.clone();
+        }
+    }
+    let outer_refs = std::mem::take(&mut data.fn_refs);
+    scope_enter(&func.block, data);
+    if let Some(frame) = data.scopes.last_mut() {
+        frame.is_fn = true;
+    }
+    // an async fn body can be suspended at its .await points
+    let async_scope = if sig.is_async { Some(data.scopes.len()-1) } else { None };
+    let outer_async = std::mem::replace(&mut data.async_scope, async_scope);
+    // create new stack for func arg
+    for (_, p_ident, arg_rap, arg_ty) in sig.args {
+        let is_ref = matches!(&arg_rap, ResourceAccessPoint::MutRef(_) | ResourceAccessPoint::StaticRef(_));
+        if let Some(arg_arc) = var_allo_insert(Infoitem::FnArg(p_ident), 
+            arg_rap, data, stack_num+1) {
+            scope_decl(&arg_arc, data);
+            let pointer = ptr_layers_ty(&arg_ty);
+            if !pointer.is_empty() {
+                data.rap_info.entry(*arg_arc.hash()).or_default().pointer = pointer;
+            }
+            if let Some(lifetime) = ref_lifetime(&arg_ty) {
+                data.rap_info.entry(*arg_arc.hash()).or_default().lifetimes = vec![lifetime];
+            }
+            if let Some(bounds) = sig.arg_bounds.get(arg_arc.name()) {
+                data.rap_info.entry(*arg_arc.hash()).or_default().bounds = bounds.clone();
+            }
+            data.rap_info.entry(*arg_arc.hash()).or_default().ty = match &data.cur_self {
+                Some(self_ty) => self_fmt(&arg_ty, self_ty),
+                None => arg_ty,
+            };
+            if is_ref {
+                data.fn_refs.push(arg_arc);
+            }
+        }
+    }
+    // parse function block
+    for stmt in &func.block.stmts {
+        parse_stmt(stmt, data, hash_num, stack_num+1);
+    }
+    ref_die_gen(data);
+    scope_exit(data);
+    data.fn_refs = outer_refs;
+    data.cur_fn = outer_fn;
+    data.async_scope = outer_async;
+}
+
+//TODO: do I need to specify same lifetime for color_info and var_def
+fn parse_item (items: &Vec<syn::Item>, 
+    data: &mut data_pkg,
+    hash_num: &mut u64,
+    stack_num: usize) {
+    for item in items {
+        // All items refer to https://docs.rs/syn/1.0.72/syn/enum.Item.html
+        // TODO: macros, const, enums, structs... we only consider functions and struct here
+        // TODO: Add errors???
+
+        // TODO: *item and func.clone / item and *func.clone 
+        // match take ownership???
+        // clone of a reference??? or of the variable itself?
+        match item {
+            Item::Fn(func) => {
+                // register func into var_def
+                let func_name = format!("{}()", mod_name_fmt(&data.mod_path.join("::"), &format!("{}", func.sig.ident)));
+                fn_item_parse(func, func_name, data, hash_num, stack_num);
+            },
+            Item::Impl(itemimpl) => {
+                // methods are parsed like functions named Point::area()
+                let self_ty = impl_self_fmt(&itemimpl.self_ty, data);
+                let trait_name = itemimpl.trait_.as_ref().map(|(_, path, _)| trait_resolve(path, data));
+                let outer_self = data.cur_self.replace(self_ty.clone());
+                for impl_item in &itemimpl.items {
+                    if let syn::ImplItem::Method(method) = impl_item {
+                        let func = syn::ItemFn {
+                            attrs: method.attrs.clone(),
+                            vis: method.vis.clone(),
+                            sig: method.sig.clone(),
+                            block: Box::new(method.block.clone()),
+                        };
+                        let method_name = format!("{}", method.sig.ident);
+                        fn_item_parse(&func, method_key(&self_ty, trait_name.as_ref(), &method_name), data, hash_num, stack_num);
+                    }
+                }
+                data.cur_self = outer_self;
+            },
+            Item::Trait(itemtrait) => {
+                // only default method bodies have something to parse,
+                // inside them self is known by its trait only
+                let trait_name = mod_name_fmt(&data.mod_path.join("::"), &format!("{}", itemtrait.ident));
+                let outer_self = data.cur_self.replace(trait_name.clone());
+                for trait_item in &itemtrait.items {
+                    if let syn::TraitItem::Method(method) = trait_item {
+                        if let Some(block) = &method.default {
+                            let func = syn::ItemFn {
+                                attrs: method.attrs.clone(),
+                                vis: syn::Visibility::Inherited,
+                                sig: method.sig.clone(),
+                                block: Box::new(block.clone()),
+                            };
+                            fn_item_parse(&func, format!("{}::{}()", trait_name, method.sig.ident), data, hash_num, stack_num);
+                        }
+                    }
+                }
+                data.cur_self = outer_self;
+            },
+            Item::Struct(itemstruct) => {
+                // module level ones were registered by struct_collect(),
+                // this picks up structs declared inside function bodies
+                struct_insert(itemstruct, data, hash_num, stack_num);
+            },
+            Item::Mod(itemmod) => {
+                // out-of-line modules were made inline by mod_expand()
+                if let Some((_, mod_items)) = &itemmod.content {
+                    data.mod_path.push(format!("{}", itemmod.ident));
+                    let outer_file = data.cur_file.clone();
+                    if let Some(mod_file) = data.mod_files.get(&data.mod_path.join("::")) {
+                        data.cur_file = mod_file.clone();
+                    }
+                    parse_item(mod_items, data, hash_num, stack_num);
+                    data.cur_file = outer_file;
+                    data.mod_path.pop();
+                }
+            },
+            Item::Const(_) | Item::Static(_) => {
+                // module level ones were registered by global_collect(),
+                // this picks up consts declared inside function bodies
+                global_insert(item, data, hash_num, stack_num);
+            },
+            Item::Use(_) => {
+                // registered by use_alias_collect()
+            },
+            _ => info!("syn::Item option not supported")
+        }
+    }
+    // var_def
+}
+
+// used to derive info from expr parse after eq sign
+#[derive(Debug, Default)]
+struct expr_derive {
+    name: String,
+    var_mut: bool,
+    is_ref: bool,
+    ref_mut: bool,
+    is_struct: bool,
+    hash: u64,
+    ty: String,
+    ref_targets: Vec<Arc<ResourceAccessPoint>>,
+    ref_moved: bool,
+    lifetimes: Vec<String>,
+    pointer: Vec<PtrKind>,
+    binds: Vec<PendingBind>,
+}
+
+// events whose target is the let binding itself, emitted once the
+// binding's RAP is allocated
+#[derive(Debug, Clone, PartialEq)]
+enum BindEvent {
+    Move,
+    Copy,
+    SharedClone,
+    InteriorBorrow,
+    InteriorMutBorrow,
+    RawPointer,
+}
+
+// a BindEvent from its line and source, waiting for the binding's RAP
+type PendingBind = (usize, BindEvent, Option<Arc<ResourceAccessPoint>>);
+
+fn parse_stmt(stmt: &syn::Stmt, 
+    data: &mut data_pkg,
+    hash_num: &mut u64,
+    stack_num: usize) {
+    // local => let statement
+    // Item => function definition, struct definition etc.
+    // Expr => Expression without semicolon (return...)
+    // Semi => Expression with semicolon
+
+    let outer_temps = std::mem::take(&mut data.stmt_temps);
+    match stmt {
+        Stmt::Local(loc) => {
+            let mut expr_pass = expr_derive {
                 name: String::from(""),
                 var_mut: false,
                 is_ref: false,
                 ref_mut: false,
                 is_struct: false,
-                hash: hash_num.clone(),
+                hash: *hash_num,
+                ty: String::new(),
+                ref_targets: Vec::new(),
+                ref_moved: false,
+                lifetimes: Vec::new(),
+                pointer: Vec::new(),
+                binds: Vec::new(),
             };
             *hash_num+=1;
             let mut location_item = None;
@@ -755,7 +3210,7 @@ fn parse_stmt(stmt: &syn::Stmt,
                     debug!("Owner found: {}, ref_mut: {:?}, ref: {:?}", pat_ident.ident, pat_ident.mutability, pat_ident.by_ref);
                     debug!("{:?}", pat_ident.ident.span().start());
                     debug!("{:?}", pat_ident.ident.span().end());
-                    expr_pass.name = String::from(format!("{}", pat_ident.ident));
+                    expr_pass.name = format!("{}", pat_ident.ident);
                     // var ref_mut not is_ref ref_mut
                     if let Some(_mutable) = &pat_ident.mutability {
                         expr_pass.var_mut = true;
@@ -784,7 +3239,7 @@ fn parse_stmt(stmt: &syn::Stmt,
                 Pat::Type(pat_type) => {
                     if let Pat::Ident(pat_ident) = &*pat_type.pat {
                         location_item = Some(pat_ident);
-                        expr_pass.name = String::from(format!("{}", pat_ident.ident));
+                        expr_pass.name = format!("{}", pat_ident.ident);
                         if let Some(_mutability) = pat_ident.mutability {
                             expr_pass.var_mut = true;
                         }
@@ -795,74 +3250,806 @@ fn parse_stmt(stmt: &syn::Stmt,
                             expr_pass.ref_mut = true;
                         }
                     }
+                    expr_pass.ty = type_fmt(&pat_type.ty);
+                    expr_pass.pointer = ptr_layers_ty(&expr_pass.ty);
                     //TODO: add struct here
                 },
                 _ => info!("stmt not supported")
             }
 
-            //if a value or a is_ref is assigned
-            if let Some((_eq, expr)) = &loc.init {
-                parse_expr(expr, Some(&mut expr_pass), data, hash_num, stack_num);
+            if location_item.is_none() {
+                // let (a, b) = ...; let Point { x, y } = ...;
+                if let Some((_eq, expr)) = &loc.init {
+                    parse_expr(expr, None, data, hash_num, stack_num);
+                }
+                pat_bind(&loc.pat, data, hash_num, stack_num);
+            }
+            if let Some(location_item) = location_item {
+                //if a value or a is_ref is assigned
+                if let Some((_eq, expr)) = &loc.init {
+                    parse_expr(expr, Some(&mut expr_pass), data, hash_num, stack_num);
+                    init_infer(expr, &mut expr_pass, data, stack_num);
+                }
+            
+                data.uninit.remove(&expr_pass.name);
+                if loc.init.is_none() {
+                    // let x; is allocated at its first assignment,
+                    // once the value tells what x is
+                    if let Some(frame) = data.scopes.last() {
+                        let pending = PendingLocal {
+                            pat: location_item.clone(),
+                            derive: expr_pass,
+                            stack_num,
+                            scope: data.scopes.len()-1,
+                            pos: frame.decls.len(),
+                        };
+                        data.uninit.insert(format!("{}", location_item.ident), pending);
+                    }
+                } else if let Some(expr_arc) = local_insert(location_item, expr_pass, loc.span().start().line, data, stack_num) {
+                    scope_decl(&expr_arc, data);
+                }
+            }
+        },
+        Stmt::Semi(exp, _) => {
+            parse_expr(exp, None, data, hash_num, stack_num);
+            info!("{:?}", exp);
+        }, 
+        Stmt::Expr(exp) => {
+            parse_expr(exp, None, data, hash_num, stack_num);
+            if data.scopes.last().is_some_and(|frame| frame.is_fn) {
+                ret_move_insert(exp, data);
+            }
+            info!("{:?}", exp);
+        },
+        Stmt::Item(item) => {
+            parse_item(&vec![item.clone()], data, hash_num, stack_num);
+        }
+    }
+    // temporaries that were not moved away die with the statement
+    let stmt_temps = std::mem::replace(&mut data.stmt_temps, outer_temps);
+    for tmp_arc in stmt_temps.iter().rev() {
+        if !data.moved.contains(tmp_arc.hash()) {
+            event_insert(stmt.span().end().line, EventItem::GoOutOfScope { ro: tmp_arc.clone() }, data);
+        }
+    }
+    debug!("{:?}", stmt.span().start());
+    debug!("{:?}", stmt.span().end());
+    debug!("--------------");
+}
+
+fn local_insert(location_item: &syn::PatIdent,
+    expr_pass: expr_derive,
+    line: usize,
+    data: &mut data_pkg,
+    stack_num: usize) -> Option<Arc<ResourceAccessPoint>> {
+    // allocate the RAP of a let binding from what its initializer
+    // derived and record the events that bind it: moves and copies
+    // into it, the heap allocation of a new pointer, the borrows of a
+    // reference; the caller declares it in its scope
+    if !expr_pass.ty.is_empty() {
+        data.rap_info.entry(expr_pass.hash).or_default().ty = expr_pass.ty.clone();
+    }
+    let expr_rap;
+    if expr_pass.is_struct {
+        expr_rap = ResourceAccessPoint::Struct(
+            Struct {
+            name: expr_pass.name,
+            hash: expr_pass.hash,
+            owner: expr_pass.hash,
+            is_mut: expr_pass.var_mut,
+            is_member: false
+            }
+        );
+    } else {
+        if expr_pass.is_ref {
+            if expr_pass.ref_mut {
+                expr_rap = ResourceAccessPoint::MutRef(
+                    MutRef {
+                    name: expr_pass.name,
+                    hash: expr_pass.hash,
+                    is_mut: expr_pass.var_mut,
+                    }
+                );
+            } else {
+                expr_rap = ResourceAccessPoint::StaticRef( 
+                    StaticRef {
+                    name: expr_pass.name,
+                    hash: expr_pass.hash,
+                    is_mut: expr_pass.var_mut,
+                    }
+                );
+            }
+        } else {
+            expr_rap = ResourceAccessPoint::Owner(
+                Owner {
+                name: expr_pass.name,
+                hash: expr_pass.hash,
+                is_mut: expr_pass.var_mut, 
+                }
+            );
+        }
+    }
+    let expr_arc = var_allo_insert(Infoitem::Local(location_item.clone()), expr_rap,
+    data, stack_num);
+    if let Some(expr_arc) = &expr_arc {
+        if !expr_pass.pointer.is_empty() {
+            match expr_pass.pointer[0] {
+                PtrKind::Box | PtrKind::Rc | PtrKind::Arc
+                    // a fresh pointer owns its heap allocation,
+                    // a shared clone points to an existing one
+                    if !expr_pass.binds.iter().any(|(_, kind, _)| *kind == BindEvent::SharedClone) => {
+                        event_insert(line, EventItem::HeapAlloc { ro: expr_arc.clone() }, data);
+                    },
+                _ => {}
+            }
+            data.rap_info.entry(*expr_arc.hash()).or_default().pointer = expr_pass.pointer.clone();
+        }
+        for (bind_line, kind, from) in expr_pass.binds.clone() {
+            let to = Some(expr_arc.clone());
+            let event = match kind {
+                BindEvent::Move => EventItem::Move { from, to },
+                BindEvent::Copy => EventItem::Copy { from, to },
+                BindEvent::SharedClone => EventItem::SharedClone { from, to },
+                BindEvent::InteriorBorrow => EventItem::InteriorBorrow { from, to },
+                BindEvent::InteriorMutBorrow => EventItem::InteriorMutBorrow { from, to },
+                BindEvent::RawPointer => EventItem::RawPointer { from, to },
+            };
+            event_insert(bind_line, event, data);
+        }
+    }
+    if let (true, Some(ref_arc)) = (expr_pass.is_ref, expr_arc.clone()) {
+        // borrow happens at the let, the reference dies at its last use;
+        // a moved &mut carries the borrow over without a new one
+        let new_borrows = if expr_pass.ref_moved { Vec::new() } else { expr_pass.ref_targets.clone() };
+        for target in new_borrows.iter() {
+            let from = Some(target.clone());
+            let to = Some(ref_arc.clone());
+            let event = if expr_pass.ref_mut {
+                EventItem::MutableBorrow { from, to }
+            } else {
+                EventItem::StaticBorrow { from, to }
+            };
+            event_insert(line, event, data);
+        }
+        if !expr_pass.ref_targets.is_empty() {
+            data.ref_targets.insert(*ref_arc.hash(), expr_pass.ref_targets);
+            let owners = ref_owner_chain(&ref_arc, data);
+            data.rap_info.entry(*ref_arc.hash()).or_default().points_to = owners.iter().map(|owner| *owner.hash()).collect();
+        }
+        if !expr_pass.lifetimes.is_empty() {
+            data.rap_info.entry(*ref_arc.hash()).or_default().lifetimes = expr_pass.lifetimes;
+        }
+        data.fn_refs.push(ref_arc);
+    }
+    expr_arc
+}
+
+fn ptr_ctor(call_name: &str) -> Option<PtrKind> {
+    // smart pointer constructor: Box::new, std::rc::Rc::new, ...
+    let segs: Vec<&str> = call_name.rsplitn(3, "::").collect();
+    if segs.len() < 2 || segs[0] != "new" {
+        return None
+    }
+    ptr_kind(segs[1])
+}
+
+fn ptr_kind(type_name: &str) -> Option<PtrKind> {
+    match type_name {
+        "Box" => Some(PtrKind::Box),
+        "Rc" => Some(PtrKind::Rc),
+        "Arc" => Some(PtrKind::Arc),
+        "RefCell" => Some(PtrKind::RefCell),
+        "Cell" => Some(PtrKind::Cell),
+        "Ref" => Some(PtrKind::Ref),
+        "RefMut" => Some(PtrKind::RefMut),
+        _ => None,
+    }
+}
+
+fn ptr_layers(expr: &syn::Expr) -> Vec<PtrKind> {
+    // pointer layers built by nested constructors:
+    // Rc::new(RefCell::new(5)) -> [Rc, RefCell]
+    let mut layers = Vec::new();
+    if let Expr::Call(exprcall) = expr {
+        if let Expr::Path(exprpath) = &*exprcall.func {
+            if let Some(kind) = ptr_ctor(&path_fmt(exprpath)) {
+                layers.push(kind);
+                if let Some(arg) = exprcall.args.first() {
+                    layers.extend(ptr_layers(arg));
+                }
+            }
+        }
+    }
+    layers
+}
+
+fn ptr_layers_ty(ty: &str) -> Vec<PtrKind> {
+    // pointer layers of a written type:
+    // std::rc::Rc<RefCell<i32>> -> [Rc, RefCell], *const i32 -> [RawConst]
+    if ty.starts_with("*const ") {
+        return vec![PtrKind::RawConst]
+    }
+    if ty.starts_with("*mut ") {
+        return vec![PtrKind::RawMut]
+    }
+    let mut layers = Vec::new();
+    let mut rest = ty;
+    while let Some(open) = rest.find('<') {
+        let type_name = match rest[..open].rfind("::") {
+            Some(pos) => &rest[pos+2..open],
+            None => &rest[..open],
+        };
+        match ptr_kind(type_name.trim()) {
+            Some(kind) => layers.push(kind),
+            None => break,
+        }
+        rest = &rest[open+1..];
+    }
+    layers
+}
+
+fn ptr_info(ident: &String, data: &data_pkg, stack_num: usize) -> Option<(Arc<ResourceAccessPoint>, Vec<PtrKind>)> {
+    // RAP of a variable and its pointer layers
+    let rap = rap_lookup(ident, data, stack_num)?;
+    let pointer = match data.rap_info.get(rap.hash()) {
+        Some(info) => info.pointer.clone(),
+        None => Vec::new(),
+    };
+    Some((rap, pointer))
+}
+
+fn struct_lit_resolve(expr: &syn::Expr, data: &data_pkg) -> Option<String> {
+    // struct type of a struct literal expression:
+    // Point { x: 1, y: 1 } or the tuple struct Meters(3.0)
+    match expr {
+        Expr::Struct(expr_struct) => {
+            Some(mod_resolve(&syn_path_fmt(&expr_struct.path), data, |name| data.var_def.contains_key(name)))
+        },
+        Expr::Call(exprcall) => {
+            if let Expr::Path(exprpath) = &*exprcall.func {
+                let struct_type = mod_resolve(&path_fmt(exprpath), data, |name| data.struct_info.contains_key(name));
+                if let Some(StructKind::Tuple) = data.struct_info.get(&struct_type).map(|def| def.kind.clone()) {
+                    return Some(struct_type)
+                }
+            }
+            None
+        },
+        Expr::Paren(expr_paren) => struct_lit_resolve(&expr_paren.expr, data),
+        _ => None,
+    }
+}
+
+fn tmp_owner_name(expr: &syn::Expr, owner_type: &str) -> String {
+    // name of the anonymous owner of a value no let binds: a struct literal
+    // draw(Point { x: 1, y: 1 }) on line 5, column 9 -> tmp_point_5_9, or the
+    // Result a ? unwraps; the column keeps two of them on one line apart
+    let type_name = match owner_type.rfind("::") {
+        Some(pos) => &owner_type[pos+2..],
+        None => owner_type,
+    };
+    let start = expr.span().start();
+    format!("tmp_{}_{}_{}", type_name.to_lowercase(), start.line, start.column)
+}
+
+fn cur_fn_rap(data: &mut data_pkg) -> Option<Arc<ResourceAccessPoint>> {
+    // RAP of the function being parsed, values returned from it go there;
+    // main() is only allocated once something leaves it this way
+    let name = data.cur_fn.clone()?;
+    if let Some(rap) = data.var_alloc.get(&name).and_then(|raps| raps.last().cloned()) {
+        return Some(rap)
+    }
+    let rap = Arc::new(data.fn_sigs.get(&name)?.func.clone());
+    data.var_alloc.insert(name, vec![rap.clone()]);
+    Some(rap)
+}
+
+fn try_tmp_insert(expr_try: &syn::ExprTry,
+    data: &mut data_pkg,
+    hash_num: &mut u64,
+    stack_num: usize) -> Option<Arc<ResourceAccessPoint>> {
+    // File::open(p)? on line 5, column 12: the Result the call returns
+    // is held by tmp_result_5_12 until ? unwraps it or returns it
+    let ty = expr_ty(&expr_try.expr, data, stack_num);
+    let type_name = match ty.split('<').next() {
+        Some(type_name) if !type_name.is_empty() => type_name.to_string(),
+        _ => String::from("Result"),
+    };
+    let tmp_rap = ResourceAccessPoint::Owner(Owner {
+        name: tmp_owner_name(&expr_try.expr, &type_name),
+        hash: *hash_num,
+        is_mut: false,
+    });
+    *hash_num+=1;
+    let tmp_arc = var_allo_insert(Infoitem::Try(expr_try.clone()), tmp_rap, data, stack_num)?;
+    if !ty.is_empty() {
+        data.rap_info.entry(*tmp_arc.hash()).or_default().ty = ty;
+    }
+    data.stmt_temps.push(tmp_arc.clone());
+    Some(tmp_arc)
+}
+
+fn ret_move_insert(expr: &syn::Expr, data: &mut data_pkg) {
+    // a struct literal returned by a function is moved to the caller
+    // instead of dropped: fn new(w: u32) -> Rect { Rect { w } }
+    let struct_type = match struct_lit_resolve(expr, data) {
+        Some(struct_type) => struct_type,
+        None => return,
+    };
+    let tmp_name = tmp_owner_name(expr, &struct_type);
+    let tmp = data.stmt_temps.iter().find(|tmp_arc| *tmp_arc.name() == tmp_name).cloned();
+    let func = cur_fn_rap(data);
+    if let Some(tmp) = tmp {
+        event_insert(expr.span().start().line, EventItem::Move { from: Some(tmp), to: func }, data);
+    }
+}
+
+fn struct_lit_insert(expr: &syn::Expr,
+    struct_type: String,
+    stmt_pass: Option<&mut expr_derive>,
+    data: &mut data_pkg,
+    hash_num: &mut u64,
+    stack_num: usize) {
+    // a struct literal owns its members: the let it is bound to, or an
+    // anonymous temporary owner dropped at the end of the statement
+    let (owner_name, owner_hash) = match stmt_pass {
+        Some(stmt_derive) => {
+            stmt_derive.is_struct = true;
+            if stmt_derive.ty.is_empty() {
+                stmt_derive.ty = struct_type.clone();
+            }
+            (stmt_derive.name.clone(), stmt_derive.hash)
+        },
+        None => {
+            let type_ident = match expr {
+                Expr::Struct(expr_struct) => expr_struct.path.segments.last().map(|seg| seg.ident.clone()),
+                Expr::Call(exprcall) => match &*exprcall.func {
+                    Expr::Path(exprpath) => exprpath.path.segments.last().map(|seg| seg.ident.clone()),
+                    _ => None,
+                },
+                _ => None,
+            };
+            let tmp_name = tmp_owner_name(expr, &struct_type);
+            let tmp_rap = ResourceAccessPoint::Struct(
+                Struct {
+                name: tmp_name.clone(),
+                hash: *hash_num,
+                owner: *hash_num,
+                is_mut: false,
+                is_member: false,
+                }
+            );
+            let tmp_hash = *hash_num;
+            *hash_num+=1;
+            data.rap_info.entry(tmp_hash).or_default().ty = struct_type.clone();
+            if let Some(type_ident) = type_ident {
+                if let Some(tmp_arc) = var_allo_insert(Infoitem::ExprStruct(type_ident), tmp_rap, data, stack_num) {
+                    data.stmt_temps.push(tmp_arc);
+                }
+            }
+            (tmp_name, tmp_hash)
+        }
+    };
+    struct_lit_parse(expr, &struct_type, &owner_name, owner_hash, data, hash_num, stack_num);
+}
+
+fn assoc_member_insert(struct_type: &String,
+    owner_name: &String,
+    owner_hash: u64,
+    data: &mut data_pkg,
+    line: usize) {
+    // members of a struct returned by a constructor, taken from its
+    // definition since no literal is written at the call site
+    let fields: Vec<(String, String)> = match data.struct_info.get(struct_type) {
+        Some(def) => def.fields.clone(),
+        None => return,
+    };
+    for (field_name, field_ty) in fields {
+        let field = ResourceAccessPoint::Struct(
+            Struct {
+            name: field_name.clone(),
+            hash: 0,
+            owner: owner_hash,
+            is_mut: false,
+            is_member: true,
             }
-            
-            let expr_rap;
-            if expr_pass.is_struct {
-                expr_rap = ResourceAccessPoint::Struct(
-                    Struct {
-                    name: expr_pass.name,
-                    hash: expr_pass.hash.clone(),
-                    owner: expr_pass.hash.clone(),
-                    is_mut: expr_pass.var_mut,
-                    is_member: false
+        );
+        struct_member_insert(struct_type, field, data, owner_name, line);
+        // nested struct fields: Line::new() -> l.start.x
+        let inner_type = mod_resolve(&field_ty, data, |name| data.var_def.contains_key(name));
+        if data.var_def.contains_key(&inner_type) {
+            assoc_member_insert(&inner_type, &format!("{}.{}", owner_name, field_name), owner_hash, data, line);
+        }
+    }
+}
+
+fn struct_lit_parse(expr: &syn::Expr,
+    struct_type: &String,
+    owner_name: &String,
+    owner_hash: u64,
+    data: &mut data_pkg,
+    hash_num: &mut u64,
+    stack_num: usize) {
+    // register the members of a struct literal under owner_name,
+    // a member initialized by another struct literal is registered
+    // recursively: Line { start: Point { x: 0, y: 0 }, .. } -> l.start.x
+    let field_value = |data: &mut data_pkg, hash_num: &mut u64, field_name: String, value: &syn::Expr, line: usize| {
+        match struct_lit_resolve(value, data) {
+            Some(inner_type) => {
+                let inner_owner = format!("{}.{}", owner_name, field_name);
+                struct_lit_parse(value, &inner_type, &inner_owner, owner_hash, data, hash_num, stack_num);
+            },
+            None => {
+                // Point { x, y } (shorthand) and Point { x: a } both use a
+                // local as the member's source
+                parse_expr(value, None, data, hash_num, stack_num);
+                if let Expr::Path(exprpath) = value {
+                    let source = rap_lookup(&path_fmt(exprpath), data, stack_num);
+                    if source.is_some() {
+                        member_move_insert(source, struct_type, &field_name, owner_name, line, data);
                     }
-                );
-            } else {
-                if expr_pass.is_ref {
-                    if expr_pass.ref_mut {
-                        expr_rap = ResourceAccessPoint::MutRef(
-                            MutRef {
-                            name: expr_pass.name,
-                            hash: expr_pass.hash.clone(),
-                            is_mut: expr_pass.var_mut,
-                            }
-                        );
-                    } else {
-                        expr_rap = ResourceAccessPoint::StaticRef( 
-                            StaticRef {
-                            name: expr_pass.name,
-                            hash: expr_pass.hash.clone(),
-                            is_mut: expr_pass.var_mut,
+                }
+            }
+        }
+    };
+    match expr {
+        Expr::Struct(expr_struct) => {
+            let mut explicit_fields = Vec::new();
+            for i in &expr_struct.fields {
+                match &i.member {
+                    syn::Member::Named(Ident) => {
+                        let field = ResourceAccessPoint::Struct(
+                            Struct {
+                            name: format!("{}",Ident),
+                            //TODO: is it fine without clone?
+                            hash: 0,
+                            owner: owner_hash,
+                            is_mut: false,
+                            is_member: true,
                             }
                         );
+                        struct_expr_insert(Infoitem::ExprStruct(Ident.clone()),
+                        struct_type.clone(), field, data, stack_num, owner_name);
+                        explicit_fields.push(format!("{}", Ident));
+                        field_value(data, hash_num, format!("{}", Ident), &i.expr, i.span().start().line);
                     }
-                } else {
-                    expr_rap = ResourceAccessPoint::Owner(
-                        Owner {
-                        name: expr_pass.name,
-                        hash: expr_pass.hash.clone(),
-                        is_mut: expr_pass.var_mut, 
+                    _ => {
+                        info!("struct type not supported");
+                        parse_expr(&i.expr, None, data, hash_num, stack_num);
+                    }
+                }   
+            }
+            // struct update syntax: Point { x: 1, ..other }, every field
+            // not listed is moved/copied from the matching field of other
+            if let Some(rest) = &expr_struct.rest {
+                parse_expr(rest, None, data, hash_num, stack_num);
+                let base_name = expr_base(rest);
+                let rest_fields: Vec<String> = match data.struct_info.get(struct_type) {
+                    Some(def) => def.fields.iter()
+                        .map(|(name, _)| name.clone())
+                        .filter(|name| !explicit_fields.contains(name))
+                        .collect(),
+                    None => Vec::new(),
+                };
+                for field_name in rest_fields {
+                    let field = ResourceAccessPoint::Struct(
+                        Struct {
+                        name: field_name.clone(),
+                        hash: 0,
+                        owner: owner_hash,
+                        is_mut: false,
+                        is_member: true,
                         }
                     );
+                    struct_member_insert(struct_type, field, data, owner_name, rest.span().start().line);
+                    // ..Point::new(0, 0) has no RAP to take the fields from
+                    let source = match &base_name {
+                        Some(base_name) => {
+                            match data.members.get(&format!("{}.{}", base_name, field_name)) {
+                                Some(member) => Some(member.clone()),
+                                None => rap_lookup(base_name, data, stack_num),
+                            }
+                        },
+                        None => None,
+                    };
+                    member_move_insert(source, struct_type, &field_name,
+                        owner_name, rest.span().start().line, data);
                 }
             }
-            var_allo_insert(Infoitem::Local(location_item.unwrap().clone()), expr_rap,
-            data, stack_num);
         },
-        Stmt::Semi(exp, _) => {
-            parse_expr(&exp, None, data, hash_num, stack_num);
-            info!("{:?}", exp);
-        }, 
-        Stmt::Expr(exp) => {
-            parse_expr(&exp, None, data, hash_num, stack_num);
-            info!("{:?}", exp);
+        Expr::Call(exprcall) => {
+            // tuple struct, fields named by position
+            for (pos, arg) in exprcall.args.iter().enumerate() {
+                let field = ResourceAccessPoint::Struct(
+                    Struct {
+                    name: format!("{}", pos),
+                    hash: 0,
+                    owner: owner_hash,
+                    is_mut: false,
+                    is_member: true,
+                    }
+                );
+                struct_expr_insert(Infoitem::TupleArg(arg.clone()),
+                struct_type.clone(), field, data, stack_num, owner_name);
+                field_value(data, hash_num, format!("{}", pos), arg, arg.span().start().line);
+            }
         },
-        Stmt::Item(item) => {
-            parse_item(&vec![item.clone()], data, hash_num, stack_num);
+        Expr::Paren(expr_paren) => {
+            struct_lit_parse(&expr_paren.expr, struct_type, owner_name, owner_hash, data, hash_num, stack_num);
+        },
+        _ => {}
+    }
+}
+
+fn std_method_ty(method: &str) -> Option<(&'static str, Option<bool>)> {
+    // return type of common std methods, T standing for the element
+    // type of the receiver, and whether the result keeps borrowing the
+    // receiver: Some(false) shared, Some(true) mutably
+    match method {
+        "first" | "last" | "get" => Some(("Option<&T>", Some(false))),
+        "first_mut" | "last_mut" | "get_mut" => Some(("Option<&mut T>", Some(true))),
+        "iter" => Some(("Iter<T>", Some(false))),
+        "iter_mut" => Some(("IterMut<T>", Some(true))),
+        "as_str" | "trim" => Some(("&str", Some(false))),
+        "as_bytes" => Some(("&[u8]", Some(false))),
+        "as_slice" => Some(("&[T]", Some(false))),
+        "as_mut_slice" => Some(("&mut [T]", Some(true))),
+        "chars" => Some(("Chars", Some(false))),
+        "lines" => Some(("Lines", Some(false))),
+        "split" | "split_whitespace" => Some(("Split", Some(false))),
+        "keys" => Some(("Keys", Some(false))),
+        "values" => Some(("Values", Some(false))),
+        "len" | "capacity" | "count" => Some(("usize", None)),
+        "is_empty" | "contains" | "starts_with" | "ends_with" | "is_some" | "is_none"
+        | "is_ok" | "is_err" | "contains_key" | "eq" => Some(("bool", None)),
+        "to_string" | "to_owned" | "to_uppercase" | "to_lowercase" | "repeat" => Some(("String", None)),
+        "into_bytes" => Some(("Vec<u8>", None)),
+        "pop" => Some(("Option<T>", None)),
+        "remove" => Some(("T", None)),
+        _ => None,
+    }
+}
+
+fn std_call_ty(call_name: &str, arg_ty: String) -> Option<String> {
+    // return type of common std constructors: String::from("a") -> String,
+    // Box::new(5) -> Box<i32>, Some(x) -> Option<T>
+    let arg_ty = if arg_ty.is_empty() { String::from("_") } else { arg_ty };
+    let segs: Vec<&str> = call_name.rsplitn(2, "::").collect();
+    let (func, type_name) = match segs.as_slice() {
+        [func, type_name] => (*func, type_name.rsplit("::").next().unwrap_or(type_name)),
+        [func] => (*func, ""),
+        _ => return None,
+    };
+    match (type_name, func) {
+        ("String", "from") | ("String", "new") | ("String", "with_capacity") => Some(String::from("String")),
+        ("Vec", "new") | ("Vec", "with_capacity") => Some(String::from("Vec<_>")),
+        ("HashMap", "new") => Some(String::from("HashMap<_, _>")),
+        ("", "Some") => Some(format!("Option<{}>", arg_ty)),
+        ("", "Ok") => Some(format!("Result<{}, _>", arg_ty)),
+        ("", "Err") => Some(format!("Result<_, {}>", arg_ty)),
+        (type_name, "new") if ptr_kind(type_name).is_some() => Some(format!("{}<{}>", type_name, arg_ty)),
+        _ => None,
+    }
+}
+
+fn ty_elem(ty: &str) -> String {
+    // element type of a container type: Vec<i32> -> i32, &[u8] -> u8,
+    // String / &str -> char, unknown -> _
+    let ty = ty.trim_start_matches('&').trim_start_matches("mut ");
+    if ty == "String" || ty == "str" {
+        return String::from("char")
+    }
+    if ty.starts_with('[') {
+        let inner = &ty[1..ty.len()-1];
+        return inner.split(';').next().unwrap_or("_").trim().to_string()
+    }
+    match (ty.find('<'), ty.rfind('>')) {
+        (Some(open), Some(close)) if open < close => {
+            ty[open+1..close].split(", ").next().unwrap_or("_").to_string()
+        },
+        _ => String::from("_"),
+    }
+}
+
+fn expr_ty(expr: &syn::Expr, data: &data_pkg, stack_num: usize) -> String {
+    // lightweight type inference of an initializer: types of variables,
+    // literals, return types of functions in the file and a table of std
+    // signatures; empty when nothing is known
+    match expr {
+        Expr::Lit(expr_lit) => lit_ty(&expr_lit.lit),
+        Expr::Path(exprpath) => rap_lookup(&path_fmt(exprpath), data, stack_num)
+            .and_then(|rap| data.rap_info.get(rap.hash()).map(|info| info.ty.clone()))
+            .unwrap_or_default(),
+        Expr::Reference(expred) => {
+            let inner = expr_ty(&expred.expr, data, stack_num);
+            let inner = if inner.is_empty() { String::from("_") } else { inner };
+            match expred.mutability {
+                Some(_) => format!("&mut {}", inner),
+                None => format!("&{}", inner),
+            }
+        },
+        Expr::Unary(expr_unary) => {
+            let inner = expr_ty(&expr_unary.expr, data, stack_num);
+            match expr_unary.op {
+                syn::UnOp::Deref(_) if inner.starts_with("&mut ") => inner[5..].to_string(),
+                syn::UnOp::Deref(_) if inner.starts_with('&') => inner[1..].to_string(),
+                syn::UnOp::Deref(_) => String::new(),
+                _ => inner,
+            }
+        },
+        Expr::Paren(expr_paren) => expr_ty(&expr_paren.expr, data, stack_num),
+        // f().await on async fn f() -> X is X
+        Expr::Await(expr_await) => expr_ty(&expr_await.base, data, stack_num)
+            .strip_prefix("impl Future<Output = ")
+            .and_then(|rest| rest.strip_suffix('>'))
+            .map(|output| output.to_string())
+            .unwrap_or_default(),
+        Expr::Async(_) => String::from("impl Future"),
+        Expr::Try(expr_try) => try_ty(&expr_ty(&expr_try.expr, data, stack_num)),
+        Expr::Cast(expr_cast) => type_fmt(&expr_cast.ty),
+        Expr::Struct(expr_struct) => syn_path_fmt(&expr_struct.path),
+        Expr::Tuple(expr_tuple) => {
+            let elems: Vec<String> = expr_tuple.elems.iter().map(|elem| {
+                let ty = expr_ty(elem, data, stack_num);
+                if ty.is_empty() { String::from("_") } else { ty }
+            }).collect();
+            format!("({})", elems.join(", "))
+        },
+        Expr::Index(expr_index) => {
+            let base = expr_ty(&expr_index.expr, data, stack_num);
+            match &*expr_index.index {
+                Expr::Range(_) => format!("[{}]", ty_elem(&base)),
+                _ if base.is_empty() => String::new(),
+                _ => ty_elem(&base),
+            }
+        },
+        Expr::Field(expr_field) => {
+            let base = expr_ty(&expr_field.base, data, stack_num);
+            let base = base.trim_start_matches('&').trim_start_matches("mut ").to_string();
+            let field = match &expr_field.member {
+                syn::Member::Named(ident) => format!("{}", ident),
+                syn::Member::Unnamed(index) => format!("{}", index.index),
+            };
+            data.struct_info.get(&base)
+                .and_then(|def| def.fields.iter().find(|(name, _)| *name == field))
+                .map(|(_, ty)| ty.clone())
+                .unwrap_or_default()
+        },
+        Expr::Macro(expr_macro) => match expr_macro.mac.path.segments.last().map(|seg| format!("{}", seg.ident)).as_deref() {
+            Some("vec") => String::from("Vec<_>"),
+            Some("format") => String::from("String"),
+            Some(name @ "addr_of") | Some(name @ "addr_of_mut") => {
+                let pointee = syn::parse2::<syn::Expr>(expr_macro.mac.tokens.clone())
+                    .map(|place| expr_ty(&place, data, stack_num))
+                    .unwrap_or_default();
+                let pointee = if pointee.is_empty() { String::from("_") } else { pointee };
+                if name == "addr_of" { format!("*const {}", pointee) } else { format!("*mut {}", pointee) }
+            },
+            _ => String::new(),
+        },
+        Expr::Call(exprcall) => {
+            let call_name = match &*exprcall.func {
+                Expr::Path(exprpath) => path_fmt(exprpath),
+                _ => return String::new(),
+            };
+            if let Some(struct_type) = struct_lit_resolve(expr, data) {
+                return struct_type
+            }
+            let (sig_key, self_ty) = assoc_resolve(&call_name, data);
+            if let Some(sig) = data.fn_sigs.get(&sig_key) {
+                let ret_ty = match self_ty {
+                    Some(self_ty) => self_fmt(&sig.ret_ty, &self_ty),
+                    None => sig.ret_ty.clone(),
+                };
+                return future_ty(ret_ty, sig.is_async)
+            }
+            let arg_ty = exprcall.args.first().map(|arg| expr_ty(arg, data, stack_num)).unwrap_or_default();
+            std_call_ty(&call_name, arg_ty).unwrap_or_default()
+        },
+        Expr::MethodCall(exprm_call) => {
+            let method = format!("{}", exprm_call.method);
+            let recv_ty = expr_ty(&exprm_call.receiver, data, stack_num);
+            let recv = expr_base(&exprm_call.receiver).and_then(|name| rap_lookup(&name, data, stack_num));
+            if let Some(sig) = method_resolve(&method, recv.as_ref(), data).and_then(|key| data.fn_sigs.get(&key)) {
+                let self_ty = recv_ty.trim_start_matches('&').trim_start_matches("mut ").to_string();
+                let ret_ty = if self_ty.is_empty() { sig.ret_ty.clone() } else { self_fmt(&sig.ret_ty, &self_ty) };
+                return future_ty(ret_ty, sig.is_async)
+            }
+            match method.as_str() {
+                "clone" | "to_vec" => recv_ty.trim_start_matches('&').trim_start_matches("mut ").to_string(),
+                // Option<X> / Result<X, E> -> X
+                "unwrap" | "expect" | "unwrap_or_default" | "unwrap_or" => {
+                    let elem = ty_elem(&recv_ty);
+                    if elem == "_" { String::new() } else { elem }
+                },
+                _ => match std_method_ty(&method) {
+                    Some((ret, _)) => ret.replace("T", &ty_elem(&recv_ty)),
+                    None => String::new(),
+                },
+            }
+        },
+        _ => String::new(),
+    }
+}
+
+fn try_ty(ty: &str) -> String {
+    // payload ? hands on: Result<Vec<u8>, io::Error> / io::Result<Vec<u8>>
+    // / Option<Vec<u8>> -> Vec<u8>, empty for other types
+    let open = match ty.find('<') {
+        Some(open) if ty.ends_with('>') => open,
+        _ => return String::new(),
+    };
+    let type_name = match ty[..open].rfind("::") {
+        Some(pos) => &ty[pos+2..open],
+        None => &ty[..open],
+    };
+    if type_name != "Result" && type_name != "Option" {
+        return String::new()
+    }
+    ty_args(&ty[open+1..ty.len()-1]).swap_remove(0)
+}
+
+fn future_ty(ret_ty: String, is_async: bool) -> String {
+    // calling an async fn returns a future of its written return type
+    if is_async { format!("impl Future<Output = {}>", ret_ty) } else { ret_ty }
+}
+
+fn borrow_source(expr: &syn::Expr, data: &data_pkg, stack_num: usize) -> Option<(bool, Vec<Arc<ResourceAccessPoint>>)> {
+    // owners a value still borrows when it comes out of a method chain:
+    // v.first() / v.iter() borrow v, v.first().unwrap() as well,
+    // p.name() borrows p when name(&self) -> &str
+    match expr {
+        Expr::MethodCall(exprm_call) => {
+            let method = format!("{}", exprm_call.method);
+            let recv = expr_base(&exprm_call.receiver).and_then(|name| rap_lookup(&name, data, stack_num));
+            let local = method_resolve(&method, recv.as_ref(), data).and_then(|key| data.fn_sigs.get(&key));
+            let keeps = match local {
+                Some(sig) => match (&sig.ret_ref, ret_sources(sig).first()) {
+                    (Some((ret_mut, _)), Some(0)) => Some(*ret_mut),
+                    _ => None,
+                },
+                None => match method.as_str() {
+                    // Option<&T>::unwrap() and iterator adapters pass the borrow on
+                    "unwrap" | "expect" | "rev" | "skip" | "take" | "enumerate" | "peekable"
+                    | "cloned" | "copied" => return borrow_source(&exprm_call.receiver, data, stack_num),
+                    _ => std_method_ty(&method).and_then(|(_, keeps)| keeps),
+                },
+            };
+            match keeps {
+                Some(ref_mut) => {
+                    let owners = match &*exprm_call.receiver {
+                        Expr::MethodCall(_) => borrow_source(&exprm_call.receiver, data, stack_num)
+                            .map(|(_, owners)| owners).unwrap_or_default(),
+                        receiver => ref_owners(receiver, data, stack_num),
+                    };
+                    if owners.is_empty() { None } else { Some((ref_mut, owners)) }
+                },
+                None => None,
+            }
+        },
+        Expr::Paren(expr_paren) => borrow_source(&expr_paren.expr, data, stack_num),
+        _ => None,
+    }
+}
+
+fn init_infer(expr: &syn::Expr,
+    expr_pass: &mut expr_derive,
+    data: &data_pkg,
+    stack_num: usize) {
+    // no annotation: infer the type of a let from its value, and whether
+    // the value still borrows something (v.first(), v.iter(), ...)
+    if expr_pass.ty.is_empty() {
+        expr_pass.ty = expr_ty(expr, data, stack_num);
+    }
+    if !expr_pass.is_ref && !expr_pass.is_struct && expr_pass.ref_targets.is_empty() {
+        if let Some((ref_mut, owners)) = borrow_source(expr, data, stack_num) {
+            expr_pass.is_ref = true;
+            expr_pass.ref_mut = ref_mut;
+            expr_pass.ref_targets = owners;
+        } else if expr_pass.ty.starts_with('&') {
+            expr_pass.is_ref = true;
+            expr_pass.ref_mut = expr_pass.ty.starts_with("&mut ");
         }
     }
-    debug!("{:?}", stmt.span().start());
-    debug!("{:?}", stmt.span().end());
-    debug!("--------------");
 }
 
 fn parse_expr (expr: &syn::Expr, 
@@ -871,112 +4058,565 @@ fn parse_expr (expr: &syn::Expr,
     hash_num: &mut u64,
     stack_num: usize) {
 
-    println!("--------------");
     debug!("expr found");
-    println!("{:?}", expr);
     match expr {
         Expr::Assign(expr_assign) => {
-            parse_expr(&expr_assign.left, None, data, hash_num, stack_num);
-            parse_expr(&expr_assign.right, None, data, hash_num, stack_num);
+            let pending = match &*expr_assign.left {
+                Expr::Path(exprpath) => data.uninit.remove(&path_fmt(exprpath)),
+                _ => None,
+            };
+            match pending {
+                // let x; x = value; binds x like a let with that value
+                Some(pending) => local_init(pending, &expr_assign.right, expr_assign.span().start().line, data, hash_num, stack_num),
+                None => {
+                    parse_expr(&expr_assign.left, None, data, hash_num, stack_num);
+                    parse_expr(&expr_assign.right, None, data, hash_num, stack_num);
+                    place_write(&expr_assign.left, expr_assign.span().start().line, data, stack_num);
+                },
+            }
+        },
+        Expr::AssignOp(expr_assign_op) => {
+            // x += 1 reads and writes x
+            parse_expr(&expr_assign_op.left, None, data, hash_num, stack_num);
+            parse_expr(&expr_assign_op.right, None, data, hash_num, stack_num);
+            place_write(&expr_assign_op.left, expr_assign_op.span().start().line, data, stack_num);
+        },
+        Expr::Index(expr_index) => {
+            // v[i]: a use of v and of whatever the index mentions
+            match expr_base(&expr_index.expr) {
+                Some(_) => {
+                    let mut base = &*expr_index.expr;
+                    while let Expr::Index(inner) = base {
+                        base = &*inner.expr;
+                    }
+                    parse_expr(base, None, data, hash_num, stack_num);
+                },
+                None => parse_expr(&expr_index.expr, None, data, hash_num, stack_num),
+            }
+            index_parse(expr_index, data, hash_num, stack_num);
+        },
+        Expr::Range(expr_range) => {
+            if let Some(from) = &expr_range.from {
+                parse_expr(from, None, data, hash_num, stack_num);
+            }
+            if let Some(to) = &expr_range.to {
+                parse_expr(to, None, data, hash_num, stack_num);
+            }
         },
         Expr::Path(expr_path) => {
-            non_allo_insert(format!("{}", path_fmt(&expr_path)),
+            let struct_type = mod_resolve(&path_fmt(expr_path), data, |name| data.struct_info.contains_key(name));
+            if let Some(StructKind::Unit) = data.struct_info.get(&struct_type).map(|def| def.kind.clone()) {
+                // unit struct literal: let m = Marker;
+                if let Some(stmt_derive) = stmt_pass {
+                    stmt_derive.is_struct = true;
+                }
+                return
+            }
+            // locals shadow consts, consts may live in another module
+            let mut path_name = path_fmt(expr_path);
+            if rap_lookup(&path_name, data, stack_num).is_none() {
+                path_name = mod_resolve(&path_name, data, |name| data.globals.contains(name));
+            }
+            let path_arc = non_allo_insert(path_name.clone(),
                 Infoitem::Path(expr_path.clone()),
                 None, data, hash_num, stack_num);
-        }
+            if let Some(stmt_derive) = stmt_pass {
+                let path_info = path_arc.as_ref().and_then(|rap| data.rap_info.get(rap.hash())).cloned().unwrap_or_default();
+                // let b = a; moves the owner a into b, or copies it
+                if let Some(source) = owner_lookup(&path_name, data, stack_num) {
+                    let kind = if type_is_copy(&path_info.ty) || path_info.is_static {
+                        // a const is copied into every place it is used
+                        BindEvent::Copy
+                    } else {
+                        // moved out right away: a block tail is moved
+                        // before the block's own scope closes
+                        data.moved.insert(*source.hash());
+                        BindEvent::Move
+                    };
+                    if stmt_derive.ty.is_empty() {
+                        stmt_derive.ty = path_info.ty;
+                    }
+                    stmt_derive.binds.push((expr_path.span().start().line, kind, Some(source)));
+                } else if let (true, Some(ResourceAccessPoint::StaticRef(_))) = (path_info.is_static, path_arc.as_deref()) {
+                    // let x = NAME; with const NAME: &str, x points at
+                    // the same static data as NAME
+                    stmt_derive.is_ref = true;
+                    stmt_derive.ref_targets = path_arc.into_iter().collect();
+                    if stmt_derive.ty.is_empty() {
+                        stmt_derive.ty = path_info.ty;
+                    }
+                } else if let Some(path_rap) = path_arc {
+                    // let r2 = r1; copies a shared reference, moves a &mut
+                    let ref_mut = match &*path_rap {
+                        ResourceAccessPoint::MutRef(_) => true,
+                        ResourceAccessPoint::StaticRef(_) => false,
+                        _ => return,
+                    };
+                    stmt_derive.is_ref = true;
+                    stmt_derive.ref_mut = ref_mut;
+                    stmt_derive.ref_moved = ref_mut;
+                    stmt_derive.ref_targets = data.ref_targets.get(path_rap.hash()).cloned().unwrap_or_default();
+                    if stmt_derive.ty.is_empty() {
+                        stmt_derive.ty = path_info.ty;
+                    }
+                    let kind = if ref_mut { BindEvent::Move } else { BindEvent::Copy };
+                    stmt_derive.binds.push((expr_path.span().start().line, kind, Some(path_rap)));
+                }
+            }
+        },
+        Expr::Lit(expr_lit) => {
+            if let (syn::Lit::Str(lit_str), Some(stmt_derive)) = (&expr_lit.lit, stmt_pass) {
+                // let s = "hi"; borrows string data baked into the binary,
+                // every literal points into the same static_str resource
+                let lit_rap = ResourceAccessPoint::Owner(Owner {
+                    name: String::from("static_str"),
+                    hash: *hash_num,
+                    is_mut: false,
+                });
+                if let Some(lit_arc) = non_allo_insert(String::from("static_str"), Infoitem::StrLit(lit_str.clone()), Some(lit_rap), data, hash_num, stack_num) {
+                    let info = data.rap_info.entry(*lit_arc.hash()).or_default();
+                    info.is_static = true;
+                    info.ty = String::from("str");
+                    stmt_derive.is_ref = true;
+                    stmt_derive.ref_targets = vec![lit_arc];
+                    if stmt_derive.ty.is_empty() {
+                        stmt_derive.ty = String::from("&'static str");
+                    }
+                }
+            }
+        },
         Expr::Call(exprcall) => {
+            if let Some(struct_type) = struct_lit_resolve(expr, data) {
+                // tuple struct literal: Meters(3.0)
+                struct_lit_insert(expr, struct_type, stmt_pass, data, hash_num, stack_num);
+                return
+            }
+            let mut stmt_pass = stmt_pass;
+            let mut ptr_new = false;
+            if let Expr::Path(exprpath) = &*exprcall.func {
+                let call_name = path_fmt(exprpath);
+                let type_name = call_name.rsplit("::").nth(1).map(|seg| seg.to_string());
+                if let Some(stmt_derive) = stmt_pass.as_mut() {
+                    if ptr_ctor(&call_name).is_some() {
+                        // let b = Box::new(x); moves x into the allocation
+                        stmt_derive.pointer = ptr_layers(expr);
+                        ptr_new = true;
+                        if let Some(Expr::Path(argpath)) = exprcall.args.first() {
+                            if let Some(source) = rap_lookup(&path_fmt(argpath), data, stack_num) {
+                                let source_ty = data.rap_info.get(source.hash()).map(|info| info.ty.clone()).unwrap_or_default();
+                                let kind = if type_is_copy(&source_ty) { BindEvent::Copy } else { BindEvent::Move };
+                                stmt_derive.binds.push((argpath.span().start().line, kind, Some(source)));
+                            }
+                        }
+                    } else if call_name.ends_with("::clone") && (type_name == Some(String::from("Rc")) || type_name == Some(String::from("Arc"))) {
+                        // let b = Rc::clone(&a); shares a's allocation
+                        if let Some(base_name) = exprcall.args.first().and_then(ref_base) {
+                            if let Some((source, pointer)) = ptr_info(&base_name, data, stack_num) {
+                                stmt_derive.pointer = pointer;
+                                stmt_derive.binds.push((exprcall.span().start().line, BindEvent::SharedClone, Some(source)));
+                            }
+                        }
+                    }
+                }
+            }
+            let mut callee = None;
             if let Expr::Path(exprpath) = &*exprcall.func {
-                let call_rap = ResourceAccessPoint::Function(Function{name: format!("{}", path_fmt(&exprpath)), hash: hash_num.clone()});
-                non_allo_insert(format!("{}()", path_fmt(&exprpath)),
+                // shapes::area(), area() inside shapes, Self::new(), Rect::new(), ...
+                let (sig_key, self_ty) = assoc_resolve(&path_fmt(exprpath), data);
+                // a call to a function of the file shares the RAP of its definition
+                let (call_key, call_rap) = match data.fn_sigs.get(&sig_key).map(|sig| sig.func.clone()) {
+                    Some(func_rap) => {
+                        call_graph_insert(func_rap.name(), data);
+                        (func_rap.name().clone(), func_rap)
+                    },
+                    None => (sig_key.clone(), ResourceAccessPoint::Function(Function{name: sig_key.trim_end_matches("()").to_string(), hash: *hash_num})),
+                };
+                let call_arc = non_allo_insert(call_key,
                 Infoitem::Call(exprpath.clone()), Some(call_rap),
                 data, hash_num, stack_num);
+                callee = Some((sig_key, self_ty, call_arc));
+            } else {
+                // (self.op)(x), make_adder(1)(2)
+                parse_expr(&exprcall.func, None, data, hash_num, stack_num);
             }
             for arg in &exprcall.args {
                 parse_expr(arg, None, data, hash_num, stack_num);
             }
+            // match arguments against the callee's parameters
+            if let Some((callee, self_ty, call_arc)) = callee {
+                match data.fn_sigs.get(&callee).cloned() {
+                    Some(sig) => {
+                        for (pos, _, param, param_ty) in sig.args.iter() {
+                            if let Some(arg) = exprcall.args.iter().nth(*pos) {
+                                call_arg_insert(arg, param, param_ty, data, stack_num);
+                            }
+                        }
+                        // let r = Rect::new(1, 2); r owns a Rect like a struct literal would
+                        let ret_struct = self_ty.and_then(|self_ty| assoc_ret_struct(&sig, &self_ty, data));
+                        if let (Some(struct_type), Some(stmt_derive)) = (ret_struct, stmt_pass.as_mut()) {
+                            stmt_derive.is_struct = true;
+                            stmt_derive.ty = struct_type.clone();
+                            assoc_member_insert(&struct_type, &stmt_derive.name, stmt_derive.hash, data, exprcall.span().start().line);
+                            stmt_derive.binds.push((exprcall.span().start().line, BindEvent::Move, call_arc.clone()));
+                        }
+                        // let r = longest(&a, &b); borrows from a and b
+                        if let (Some((ret_mut, ret_lifetime)), Some(stmt_derive)) = (&sig.ret_ref, stmt_pass.as_mut()) {
+                            stmt_derive.is_ref = true;
+                            stmt_derive.ref_mut = *ret_mut;
+                            stmt_derive.lifetimes = ret_lifetime.iter().cloned().collect();
+                            for pos in ret_sources(&sig) {
+                                if let Some(arg) = exprcall.args.iter().nth(pos) {
+                                    for owner in ref_owners(arg, data, stack_num) {
+                                        if !stmt_derive.ref_targets.iter().any(|target| target.hash() == owner.hash()) {
+                                            stmt_derive.ref_targets.push(owner);
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    None => {
+                        // make(Point { x: 1, y: 1 }): no signature to look at,
+                        // a struct literal passed by value is moved into the call;
+                        // let b = Box::new(Point { x: 1, y: 1 }); b owns it
+                        for arg in &exprcall.args {
+                            if let Some(struct_type) = struct_lit_resolve(arg, data) {
+                                if let Some(tmp) = rap_lookup(&tmp_owner_name(arg, &struct_type), data, stack_num) {
+                                    match stmt_pass.as_mut() {
+                                        Some(stmt_derive) if ptr_new => {
+                                            data.moved.insert(*tmp.hash());
+                                            stmt_derive.binds.push((arg.span().start().line, BindEvent::Move, Some(tmp)));
+                                        },
+                                        _ => {
+                                            let event = EventItem::PassByMoveTo { from: Some(tmp), to: call_arc.clone() };
+                                            event_insert(arg.span().start().line, event, data);
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
         },
         Expr::MethodCall(exprm_call) => {
-            let m_call = String::from(format!("{}", exprm_call.method));
+            let m_call = format!("{}", exprm_call.method);
             debug!("func found: {}",  m_call);
-            let mcall_rap = ResourceAccessPoint::Function(Function{name: format!("{}",  m_call), hash: hash_num.clone()});
-            non_allo_insert(format!("{}()", m_call),
+            // local methods: Point::area(), <Point as Shape>::area(), Shape::area()
+            let recv = expr_base(&exprm_call.receiver).and_then(|name| rap_lookup(&name, data, stack_num));
+            let method_sig = method_resolve(&m_call, recv.as_ref(), data).and_then(|key| data.fn_sigs.get(&key).cloned());
+            let (mcall_name, mcall_rap) = match &method_sig {
+                Some(sig) => {
+                    call_graph_insert(sig.func.name(), data);
+                    (sig.func.name().clone(), sig.func.clone())
+                },
+                None => (format!("{}()", m_call), ResourceAccessPoint::Function(Function{name: m_call.clone(), hash: *hash_num})),
+            };
+            let mcall_arc = non_allo_insert(mcall_name,
             Infoitem::MethodCall(exprm_call.clone()),
             Some(mcall_rap), data, hash_num, stack_num);
+            // smart pointer methods: a.clone() on Rc/Arc, c.borrow() on RefCell
+            if let Some((recv_rap, pointer)) = expr_base(&exprm_call.receiver).and_then(|name| ptr_info(&name, data, stack_num)) {
+                let line = exprm_call.method.span().start().line;
+                let shared = pointer.first() == Some(&PtrKind::Rc) || pointer.first() == Some(&PtrKind::Arc);
+                let interior = pointer.contains(&PtrKind::RefCell);
+                let bind = if m_call == "clone" && shared {
+                    Some((BindEvent::SharedClone, pointer.clone()))
+                } else if m_call == "borrow" && interior {
+                    Some((BindEvent::InteriorBorrow, vec![PtrKind::Ref]))
+                } else if m_call == "borrow_mut" && interior {
+                    Some((BindEvent::InteriorMutBorrow, vec![PtrKind::RefMut]))
+                } else {
+                    None
+                };
+                match (bind, stmt_pass) {
+                    (Some((kind, bind_pointer)), Some(stmt_derive)) => {
+                        stmt_derive.pointer = bind_pointer;
+                        stmt_derive.binds.push((line, kind, Some(recv_rap)));
+                    },
+                    (Some((kind, _)), None) => {
+                        // temporary guard: c.borrow_mut().push(1)
+                        let from = Some(recv_rap);
+                        let event = match kind {
+                            BindEvent::InteriorBorrow => EventItem::InteriorBorrow { from, to: mcall_arc.clone() },
+                            BindEvent::InteriorMutBorrow => EventItem::InteriorMutBorrow { from, to: mcall_arc.clone() },
+                            _ => EventItem::SharedClone { from, to: mcall_arc.clone() },
+                        };
+                        event_insert(line, event, data);
+                    },
+                    _ => {}
+                }
+            }
 
-            parse_expr(&*exprm_call.receiver, None, data, hash_num, stack_num);
+            parse_expr(&exprm_call.receiver, None, data, hash_num, stack_num);
             for arg in &exprm_call.args {
                 parse_expr(arg, None, data, hash_num, stack_num);
             }
+            // implicit borrow/move of the receiver, the resolved method
+            // first, then methods of the receiver's type in local impls
+            let self_param = method_sig.as_ref().and_then(|sig| sig.args.first())
+                .filter(|(_, p_ident, _, _)| p_ident == "self")
+                .map(|(_, _, param, _)| param.clone());
+            if let Some(recv_rap) = recv {
+                let recv_ty = data.rap_info.get(recv_rap.hash()).map(|info| info.ty.clone()).unwrap_or_default();
+                let kind = match (&self_param, data.method_recv.get(&format!("{}::{}", type_name(&recv_ty), m_call))) {
+                    (Some(ResourceAccessPoint::MutRef(_)), _) => Some(RecvKind::MutBorrow),
+                    (Some(ResourceAccessPoint::StaticRef(_)), _) => Some(RecvKind::Borrow),
+                    (Some(_), _) => Some(RecvKind::Move),
+                    (None, Some(kind)) => Some(kind.clone()),
+                    (None, None) => std_recv_kind(&m_call),
+                };
+                if let Some(kind) = kind {
+                    // o.unwrap() on an Option<i32> copies o
+                    let from = Some(recv_rap);
+                    let event = match kind {
+                        RecvKind::Borrow => EventItem::PassByStaticReference { from, to: mcall_arc },
+                        RecvKind::MutBorrow => EventItem::PassByMutableReference { from, to: mcall_arc },
+                        RecvKind::Move if type_is_copy(&recv_ty) => EventItem::Copy { from, to: mcall_arc },
+                        RecvKind::Move => EventItem::PassByMoveTo { from, to: mcall_arc },
+                    };
+                    event_insert(exprm_call.method.span().start().line, event, data);
+                }
+            }
+            // remaining arguments against the method's parameters after self
+            if let (Some(sig), Some(_)) = (&method_sig, &self_param) {
+                for (pos, _, param, param_ty) in sig.args.iter().skip(1) {
+                    if let Some(arg) = exprm_call.args.iter().nth(*pos - 1) {
+                        call_arg_insert(arg, param, param_ty, data, stack_num);
+                    }
+                }
+            }
+        },
+        Expr::Await(expr_await) => {
+            // let r = load(&a).await; binds the output of the callee
+            match &*expr_await.base {
+                Expr::Call(_) | Expr::MethodCall(_) => parse_expr(&expr_await.base, stmt_pass, data, hash_num, stack_num),
+                base => parse_expr(base, None, data, hash_num, stack_num),
+            }
+            await_insert(expr_await.await_token.span.start().line, data);
+        },
+        Expr::Try(expr_try) => {
+            // let f = File::open(p)?; binds the Ok payload or returns
+            // early, moving the error out to the caller
+            let mut stmt_pass = stmt_pass;
+            let source = match &*expr_try.expr {
+                Expr::Call(_) | Expr::MethodCall(_) | Expr::Await(_) => {
+                    parse_expr(&expr_try.expr, stmt_pass.as_deref_mut(), data, hash_num, stack_num);
+                    try_tmp_insert(expr_try, data, hash_num, stack_num)
+                },
+                Expr::Path(exprpath) => {
+                    // let v = res?; consumes res
+                    parse_expr(&expr_try.expr, None, data, hash_num, stack_num);
+                    rap_lookup(&path_fmt(exprpath), data, stack_num)
+                },
+                base => {
+                    parse_expr(base, None, data, hash_num, stack_num);
+                    None
+                },
+            };
+            let line = expr_try.question_token.span.start().line;
+            if let (Some(source), Some(stmt_derive)) = (&source, stmt_pass) {
+                // an Option<i32> operand is copied, not consumed
+                let bind = match data.rap_info.get(source.hash()) {
+                    Some(info) if type_is_copy(&info.ty) => BindEvent::Copy,
+                    _ => BindEvent::Move,
+                };
+                stmt_derive.binds.push((line, bind, Some(source.clone())));
+            }
+            let caller = cur_fn_rap(data);
+            event_insert(line, EventItem::EarlyReturn { from: source, to: caller }, data);
+        },
+        Expr::Async(expr_async) => {
+            let mut paths = PathCollector { names: Vec::new() };
+            paths.visit_block(&expr_async.block);
+            capture_insert(paths.names, expr_async.capture.is_some(), expr_async.span().start().line,
+                stmt_pass, data, stack_num);
+            async_block_parse(expr_async, data, hash_num, stack_num);
+        },
+        Expr::Closure(expr_closure) => {
+            capture_insert(closure_captures(expr_closure), expr_closure.capture.is_some(),
+                expr_closure.span().start().line, stmt_pass, data, stack_num);
+            let mut walker = ExprWalker {
+                data,
+                hash_num,
+                stack_num,
+            };
+            walker.visit_expr_closure(expr_closure);
         },
         Expr::Reference(expred) => {
             debug!("Owner's a is_ref: {:?}", expred.mutability);
-            if let Some(stmt_derive) = stmt_pass {
-                stmt_derive.is_ref = true;
-                if let Some(_mutable) = &expred.mutability {
-                    stmt_derive.ref_mut = true;
-                }
+            let mut stmt_pass = stmt_pass;
+            let raw_mut = match stmt_pass.as_ref().and_then(|stmt_derive| stmt_derive.pointer.first()) {
+                Some(PtrKind::RawConst) => Some(false),
+                Some(PtrKind::RawMut) => Some(true),
+                _ => None,
+            };
+            if let Some(raw_mut) = raw_mut {
+                // let p: *const i32 = &x; coerces to a raw pointer
+                raw_ptr_insert(&expred.expr, raw_mut, expred.span().start().line,
+                    stmt_pass, data, hash_num, stack_num);
+                return
             }
-            
+            let mut ref_target = None;
             if let Expr::Path(exprpath) = &*expred.expr {
                 // println!("Ref target: {:?}", exprpath);
-                debug!(" Ref target: {}", path_fmt(&exprpath));
-                non_allo_insert(format!("{}", path_fmt(&exprpath)),
+                debug!(" Ref target: {}", path_fmt(exprpath));
+                ref_target = non_allo_insert(path_fmt(exprpath).to_string(),
+                Infoitem::Reference(expred.clone()),
+                None, data, hash_num, stack_num);
+            } else if let Expr::Index(expr_index) = &*expred.expr {
+                // &v[0], &mut v[1..3]: borrow of v as a whole
+                if let Some(base) = expr_base(&expr_index.expr) {
+                    ref_target = non_allo_insert(base,
+                    Infoitem::Reference(expred.clone()),
+                    None, data, hash_num, stack_num);
+                } else {
+                    parse_expr(&expr_index.expr, None, data, hash_num, stack_num);
+                }
+                index_parse(expr_index, data, hash_num, stack_num);
+            } else if let Some(inner) = reborrow_base(&expred.expr) {
+                // &*r1, &mut **r, &&x: a new reference to what r1 / &x
+                // points to
+                ref_target = non_allo_insert(inner,
                 Infoitem::Reference(expred.clone()),
                 None, data, hash_num, stack_num);
+                if let (Expr::Unary(_), Some(reborrowed)) = (&*expred.expr, &ref_target) {
+                    let owners = ref_owner_chain(reborrowed, data);
+                    if !owners.is_empty() {
+                        ref_target = None;
+                        if let Some(stmt_derive) = stmt_pass.as_mut() {
+                            stmt_derive.ref_targets = owners;
+                        }
+                    }
+                }
+            } else if let (Expr::Field(_), Some(base)) = (&*expred.expr, expr_base(&expred.expr)) {
+                // &p.x borrows the member of p, &r.0 through a reference
+                // borrows from the owners r points to
+                parse_expr(&expred.expr, None, data, hash_num, stack_num);
+                let field = expred.expr.to_token_stream().to_string().replace(' ', "");
+                if let Some(base_rap) = rap_lookup(&base, data, stack_num) {
+                    let owners = ref_owner_chain(&base_rap, data);
+                    if !owners.is_empty() {
+                        if let Some(stmt_derive) = stmt_pass.as_mut() {
+                            stmt_derive.ref_targets = owners;
+                        }
+                    } else {
+                        ref_target = Some(data.members.get(&field).cloned().unwrap_or(base_rap));
+                    }
+                }
+            } else {
+                // &Point { x: 1, y: 1 }, &make(): whatever the borrowed
+                // expression uses
+                parse_expr(&expred.expr, None, data, hash_num, stack_num);
+            }
+            if let Some(stmt_derive) = stmt_pass {
+                stmt_derive.is_ref = true;
+                if let Some(_mutable) = &expred.mutability {
+                    stmt_derive.ref_mut = true;
+                }
+                if ref_target.is_some() || stmt_derive.ref_targets.is_empty() {
+                    stmt_derive.ref_targets = ref_target.into_iter().collect();
+                }
             }
         },
         Expr::Block(expr_block) => {
             debug!("found block");
-            data.color_info.push(HashMap::new());
-            for stmt in &expr_block.block.stmts {
-                parse_stmt(&stmt, data, hash_num, stack_num+1);
+            match &expr_block.label {
+                // 'a: { .. break 'a c; .. }
+                Some(label) => exit_scope_parse(&expr_block.block, Some(label), false, stmt_pass, data, hash_num, stack_num),
+                None => block_parse(&expr_block.block, stmt_pass, data, hash_num, stack_num),
+            }
+        },
+        Expr::Unsafe(expr_unsafe) => {
+            // unsafe { .. }: a block of its own
+            block_parse(&expr_unsafe.block, stmt_pass, data, hash_num, stack_num);
+        },
+        Expr::Loop(expr_loop) => {
+            exit_scope_parse(&expr_loop.body, expr_loop.label.as_ref(), true, stmt_pass, data, hash_num, stack_num);
+        },
+        Expr::Break(expr_break) => {
+            let exit = exit_target(expr_break.label.as_ref(), data);
+            let mut carried = Vec::new();
+            if let Some(value) = &expr_break.expr {
+                // an unlabelled break carries its value out of the innermost loop
+                match exit {
+                    Some(pos) if data.loop_exits[pos].bound => {
+                        let mut value_pass = expr_derive::default();
+                        parse_expr(value, Some(&mut value_pass), data, hash_num, stack_num);
+                        carried = value_pass.binds.iter()
+                            .filter(|(_, kind, _)| *kind == BindEvent::Move)
+                            .filter_map(|(_, _, from)| from.as_ref().map(|from| *from.hash()))
+                            .collect();
+                        data.loop_exits[pos].binds.extend(value_pass.binds);
+                    },
+                    _ => parse_expr(value, None, data, hash_num, stack_num),
+                }
+            }
+            scope_jump(exit, &carried, expr_break.break_token.span.start().line, data);
+        },
+        Expr::Continue(expr_continue) => {
+            let exit = exit_target(expr_continue.label.as_ref(), data);
+            scope_jump(exit, &[], expr_continue.continue_token.span.start().line, data);
+        },
+        Expr::Cast(expr_cast) => {
+            // &x as *const i32: a raw pointer to x, not a borrow
+            let raw_mut = match &*expr_cast.ty {
+                Type::Ptr(type_ptr) => Some(type_ptr.mutability.is_some()),
+                _ => None,
+            };
+            match (raw_mut, &*expr_cast.expr) {
+                (Some(raw_mut), Expr::Reference(expred)) => {
+                    raw_ptr_insert(&expred.expr, raw_mut, expr_cast.span().start().line,
+                        stmt_pass, data, hash_num, stack_num);
+                },
+                // &mut x as *mut i32 as *mut u8
+                (Some(_), inner @ Expr::Cast(_)) => parse_expr(inner, stmt_pass, data, hash_num, stack_num),
+                (_, inner) => parse_expr(inner, None, data, hash_num, stack_num),
+            }
+        },
+        Expr::Macro(expr_macro) if raw_macro(expr_macro).is_some() => {
+            // ptr::addr_of!(x) / addr_of_mut!(x)
+            let raw_mut = raw_macro(expr_macro).unwrap_or_default();
+            match syn::parse2::<syn::Expr>(expr_macro.mac.tokens.clone()) {
+                Ok(place) => raw_ptr_insert(&place, raw_mut, expr_macro.span().start().line,
+                    stmt_pass, data, hash_num, stack_num),
+                Err(_) => debug!("addr_of macro parse error"),
             }
         },
         Expr::Binary(expr_bin) => {
             parse_expr(&expr_bin.left, None, data, hash_num, stack_num);
             parse_expr(&expr_bin.right, None, data, hash_num, stack_num);
         }, 
+        Expr::Return(expr_return) => {
+            if let Some(ret_expr) = &expr_return.expr {
+                parse_expr(ret_expr, None, data, hash_num, stack_num);
+                ret_move_insert(ret_expr, data);
+            }
+        },
         Expr::Unary(expr_unary) => {
-            if let Expr::Path(exprpath) = &*expr_unary.expr {
-                // println!("Ref target: {:?}", exprpath);
-                debug!(" Ref target: {}", path_fmt(&exprpath));
-                non_allo_insert(format!("{}", path_fmt(&exprpath)),
-                Infoitem::Dereference(expr_unary.clone()),
-                None, data, hash_num, stack_num);
+            match expr_unary.op {
+                syn::UnOp::Deref(_) => deref_parse(expr_unary, data, hash_num, stack_num),
+                // !x, -x are plain uses of x
+                _ => parse_expr(&expr_unary.expr, None, data, hash_num, stack_num),
             }
         },
         Expr::Struct(expr_struct) => {
             debug!("found struct");
-            let struct_type = format!("{}", expr_struct.path.segments[expr_struct.path.segments.len()-1].ident);
-            if let Some(stmt_derive) = stmt_pass {
-                stmt_derive.is_struct = true;
-                let owner_hash = stmt_derive.hash.clone();
-                for i in &expr_struct.fields {
-                    match &i.member {
-                        syn::Member::Named(Ident) => {
-                            let field = ResourceAccessPoint::Struct(
-                                Struct {
-                                name: format!("{}",Ident),
-                                //TODO: is it fine without clone?
-                                hash: 0,
-                                owner: owner_hash,
-                                is_mut: false,
-                                is_member: true,
-                                }
-                            );
-                            struct_expr_insert(Infoitem::ExprStruct(Ident.clone()),
-                            struct_type.clone(), field, data, stack_num, &stmt_derive.name);
-                        }
-                        _ => {
-                            info!("struct type not supported")
-                        }
-                    }   
-                    parse_expr(&i.expr, None, data, hash_num, stack_num);
-                }
+            match struct_lit_resolve(expr, data) {
+                Some(struct_type) => struct_lit_insert(expr, struct_type, stmt_pass, data, hash_num, stack_num),
+                None => {
+                    // a struct we have no definition for: still walk its fields
+                    for field in expr_struct.fields.iter() {
+                        parse_expr(&field.expr, None, data, hash_num, stack_num);
+                    }
+                    if let Some(rest) = &expr_struct.rest {
+                        parse_expr(rest, None, data, hash_num, stack_num);
+                    }
+                },
             }
         },
         Expr::Field(expr_field) => {
-            // parse_expr(&*expr_field.base, None, data, hash_num, stack_num);
+            parse_expr(&expr_field.base, None, data, hash_num, stack_num);
             // if let Expr::Path(base_expr) = &*expr_field.base {
             //     let base_name = format!("{}", path_fmt(&base_expr));
             //     match &expr_field.member {
@@ -994,66 +4634,35 @@ fn parse_expr (expr: &syn::Expr,
             debug!("found macro");
             let macro_path = &_macro.mac.path;
             if let Some(macro_func) = macro_path.segments.first() {
-                //only consider Println and assert here
-                let macro_rap = ResourceAccessPoint::Function(Function{name: format!("{}!()", macro_func.ident), hash: hash_num.clone()});
+                let macro_rap = ResourceAccessPoint::Function(Function{name: format!("{}!()", macro_func.ident), hash: *hash_num});
                 non_allo_insert(format!("{}!()", macro_func.ident),
                 Infoitem::Macro(macro_func.clone()),
                 Some(macro_rap), data, hash_num, stack_num);
 
-                if macro_func.ident.to_string() == "println" {
-                    let mut tokentree_buff = Vec::new();
-                    let mut first_lit = false;
-                    for item in _macro.mac.tokens.clone() {
-                        // print!("MACRO ITEM:{:?}",item);
-                        match item.clone() {
-                            proc_macro2::TokenTree::Punct(punct) => {
-                                if punct.as_char() == ','{
-                                    if !first_lit {
-                                        // dump "{:?...}" in println!()
-                                        tokentree_buff.clear();
-                                        first_lit = true;
-                                    } else {
-                                        let mut tokenstream_buff = proc_macro2::TokenStream::new();
-                                        tokenstream_buff.extend(tokentree_buff);
-                                        let res: Result<syn::Expr, syn::Error> = syn::parse2(tokenstream_buff);
-                                        match res {
-                                            Ok(exp) => parse_expr(&exp, None, data, hash_num, stack_num),
-                                            Err(_) => debug!("Assert macro parse error"),
-                                        }
-                                        tokentree_buff = Vec::new();
-                                    }
-                                } else {
-                                    tokentree_buff.push(item);
-                                }
-                            }
-                            _ => {
-                                tokentree_buff.push(item);
-                            }
+                // the arguments of println!, format!, assert_eq!, write!, vec!...
+                // are expressions separated by commas (vec![x; n] by a semicolon)
+                let args = _macro.mac.parse_body_with(syn::punctuated::Punctuated::<Expr, syn::Token![,]>::parse_terminated)
+                    .or_else(|_| _macro.mac.parse_body_with(syn::punctuated::Punctuated::<Expr, syn::Token![;]>::parse_terminated).map(|args| args.into_iter().collect()));
+                match args {
+                    Ok(args) => {
+                        for arg in args.iter() {
+                            parse_expr(arg, None, data, hash_num, stack_num);
                         }
-                    }
-                    let mut tokenstream_buff = proc_macro2::TokenStream::new();
-                    tokenstream_buff.extend(tokentree_buff);
-                    println!("LAST: {:?}", tokenstream_buff);
-                    let res: Result<syn::Expr, syn::Error> = syn::parse2(tokenstream_buff);
-                    println!("LAST: {:?}", res);
-                    match res {
-                        Ok(exp) => parse_expr(&exp, None, data, hash_num, stack_num),
-                        Err(_) => debug!("Assert macro parse error"),
-                    }
-                } else if macro_func.ident.to_string() == "assert" {
-                    let res: Result<syn::Expr, syn::Error> = syn::parse2(_macro.mac.tokens.clone());
-                    match res {
-                        Ok(exp) => parse_expr(&exp, None, data, hash_num, stack_num),
-                        Err(_) => debug!("Assert macro parse error"),
-                    }
-                    // parse_expr(res, local, var_def);
-                } else {
-                    info!("macro type not supported")
+                    },
+                    Err(_) => info!("macro arguments not supported"),
                 }
             }
         },
-        // do not care other right side experssion
-        _ => info!("expr not supported")
+        // everything else: no RustViz event of its own, descend into
+        // its sub-expressions, blocks and pattern bindings
+        _ => {
+            let mut walker = ExprWalker {
+                data,
+                hash_num,
+                stack_num,
+            };
+            syn::visit::visit_expr(&mut walker, expr);
+        }
     }
     debug!("{:?}", expr.span().start());
     debug!("{:?}", expr.span().end());
@@ -0,0 +1,21 @@
+struct Point { x: i32 }
+
+fn main() {
+    let mut x = 1;
+    x += 1;
+    let y = 2;
+    y = 3;
+    let mut p = Point { x: 0 };
+    p.x = 5;
+    let r = &mut x;
+    *r = 4;
+    let s;
+    s = &y;
+    let z;
+    if y > 1 {
+        z = 1;
+    } else {
+        z = 2;
+    }
+    println!("{} {} {}", p.x, s, z);
+}
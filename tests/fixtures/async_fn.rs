@@ -0,0 +1,20 @@
+async fn load(s: &String) -> usize {
+    s.len()
+}
+
+async fn run() {
+    let name = String::from("a");
+    let n = load(&name).await;
+    let greeting = String::from("hi");
+    let count = 3;
+    let task = async move {
+        let inner = count + 1;
+        println!("{} {}", greeting, inner);
+    };
+    task.await;
+    println!("{}", n);
+}
+
+fn main() {
+    let fut = run();
+}
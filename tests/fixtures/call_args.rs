@@ -0,0 +1,13 @@
+fn takes(n: i32) {}
+fn take_s(s: String) {}
+fn borrow(s: &String) {}
+fn change(s: &mut String) {}
+fn main() {
+    let x = 5;
+    takes(x);
+    let mut s = String::from("a");
+    borrow(&s);
+    change(&mut s);
+    take_s(s);
+    println!("{}", x);
+}
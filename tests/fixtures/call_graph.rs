@@ -0,0 +1,15 @@
+struct Counter { n: i32 }
+impl Counter {
+    fn new() -> Self {
+        Self::start()
+    }
+    fn start() -> Counter {
+        Counter { n: 0 }
+    }
+}
+fn helper() {}
+fn main() {
+    let c = Counter::new();
+    helper();
+    helper();
+}
@@ -0,0 +1,23 @@
+fn main() {
+    let r = Rect::new(1, 2);
+    let s = Rect::square(3);
+    println!("{}", r.w + s.h);
+}
+
+struct Rect {
+    w: u32,
+    h: u32,
+}
+
+impl Rect {
+    fn new(w: u32, h: u32) -> Rect {
+        Rect { w, h }
+    }
+
+    fn square(side: u32) -> Rect {
+        if side == 0 {
+            return Rect { w: 1, h: 1 };
+        }
+        Rect { w: side, h: side }
+    }
+}
@@ -0,0 +1,8 @@
+fn main() {
+    let n = 5;
+    let r = &n;
+    let rr = &r;
+    let m = **rr + 1;
+    let neg = -n;
+    let ok = !true;
+}
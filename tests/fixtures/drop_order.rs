@@ -0,0 +1,18 @@
+fn takes(n: i32) {}
+fn take_s(s: String) {}
+fn main() {
+    let a = String::from("a");
+    let x = 5;
+    let b = String::from("b");
+    takes(x);
+    take_s(b);
+    {
+        let c = String::from("c");
+    }
+    let d = a;
+    let y = {
+        let inner = String::from("i");
+        inner
+    };
+    let z = x;
+}
@@ -0,0 +1,17 @@
+fn take(s: String) {}
+fn main() {
+    let a = String::from("a");
+    let b = String::from("b");
+    let n = 3;
+    let t = (n as i64, [n; 2]);
+    let r = if n > 1 { &a } else { &b };
+    match n {
+        1 => take(a),
+        _ => {}
+    }
+    let v = 'outer: loop {
+        let c = String::from("c");
+        break 'outer c;
+    };
+    println!("{} {:?} {}", r, t, v);
+}
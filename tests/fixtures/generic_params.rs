@@ -0,0 +1,8 @@
+use std::fmt::Display;
+fn show<T: Display>(t: T) where T: Clone {}
+fn describe(d: impl Display, items: &[i32], pair: (i32, String), grid: [u8; 4], obj: &mut dyn Display) {}
+fn swap((a, b): (i32, i32)) {}
+fn main() {
+    let s = String::from("x");
+    show(s);
+}
@@ -0,0 +1,8 @@
+fn main() {
+    let mut v = vec![1, 2, 3];
+    let i = 1;
+    let first = &v[0];
+    let part = &v[i..3];
+    println!("{} {:?}", first, part);
+    v[i] = 5;
+}
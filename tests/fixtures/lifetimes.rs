@@ -0,0 +1,9 @@
+fn longest<'a>(x: &'a str, y: &'a str) -> &'a str {
+    if x.len() > y.len() { x } else { y }
+}
+fn main() {
+    let a = String::from("long");
+    let b = String::from("short");
+    let l = longest(&a, &b);
+    println!("{}", l);
+}
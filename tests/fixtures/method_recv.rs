@@ -0,0 +1,15 @@
+struct Stack { items: Vec<i32> }
+impl Stack {
+    fn len(self) -> usize { 0 }
+}
+fn main() {
+    let o: Option<i32> = Some(3);
+    o.unwrap();
+    o.is_some();
+    let v: Vec<i32> = Vec::new();
+    v.len();
+    let st = Stack { items: Vec::new() };
+    st.len();
+    let s = String::from("a");
+    let b = s.into_bytes();
+}
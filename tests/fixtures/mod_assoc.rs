@@ -0,0 +1,15 @@
+fn main() {
+    let r = shapes::Rect::new(1, 2);
+    let a = r.area();
+}
+mod shapes {
+    pub struct Rect { w: i32, h: i32 }
+    impl Rect {
+        pub fn new(w: i32, h: i32) -> Rect {
+            Rect { w, h }
+        }
+        pub fn area(&self) -> i32 {
+            self.w * self.h
+        }
+    }
+}
@@ -0,0 +1,7 @@
+mod shapes;
+mod missing;
+fn main() {
+    let s = String::from("a");
+    measure(&s);
+}
+use shapes::measure;
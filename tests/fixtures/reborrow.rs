@@ -0,0 +1,17 @@
+fn main() {
+    let mut s = String::from("s");
+    let r1 = &mut s;
+    let r2 = &mut *r1;
+    r2.push('a');
+    let r3 = r1;
+    r3.push('b');
+    let x = 1;
+    let a = &x;
+    let b = a;
+    let c = &&x;
+    println!("{} {} {}", a, b, c);
+    let t = (String::from("t"), 1);
+    let rt = &t;
+    let f = &rt.0;
+    println!("{}", f);
+}
@@ -0,0 +1,11 @@
+fn main() {
+    let mut s = String::from("a");
+    let r = &s;
+    let n = r.len();
+    let m = &mut s;
+    m.push('b');
+    println!("{}", n);
+    let q = &s;
+    let u = format!("{}", q);
+    assert_eq!(q.len(), 1);
+}
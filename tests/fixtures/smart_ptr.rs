@@ -0,0 +1,14 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+struct Sq(f64);
+struct Point { x: i32 }
+fn main() {
+    let b = Box::new(5);
+    let shared = Rc::new(RefCell::new(vec![1]));
+    let other = Rc::clone(&shared);
+    let r = shared.borrow();
+    let mut w = other.borrow_mut();
+    w.push(2);
+    let sq = Box::new(Sq(3.0));
+    let p = Rc::new(Point { x: 1 });
+}
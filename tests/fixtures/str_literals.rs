@@ -0,0 +1,9 @@
+fn main() {
+    let a = "hi"; let b = "there";
+    let c: &str = "again";
+    println!("{} {} {} {}", a, b, c, LIMIT);
+    let x = NAME;
+    println!("{}", x);
+}
+const LIMIT: u32 = 10;
+const NAME: &str = "rustviz";
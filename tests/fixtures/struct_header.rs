@@ -0,0 +1,9 @@
+struct Point { y: i32, x: i32 }
+struct Line { start: Point, end: Point }
+struct Marker;
+fn main() {
+    let p = Point { y: 1, x: 2 };
+    let l = Line { start: Point { y: 3, x: 4 }, end: p };
+    let k = Marker;
+    let q = Point { x: 5, z: 6, y: 7 };
+}
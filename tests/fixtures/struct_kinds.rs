@@ -0,0 +1,7 @@
+struct Meters(f64);
+struct Marker;
+struct Wrapper<'a, T> { val: &'a T, n: i32 }
+fn main() {
+    let m = Meters(3.0);
+    let k = Marker;
+}
@@ -0,0 +1,8 @@
+struct Rect { w: i32, h: i32 }
+fn takes(a: Rect, b: Rect) {}
+fn show(r: &Rect) {}
+fn main() {
+    takes(Rect { w: 1, h: 2 }, Rect { w: 3, h: 4 });
+    show(&Rect { w: 5, h: 6 });
+    let b = Box::new(Rect { w: 7, h: 8 });
+}
@@ -0,0 +1,11 @@
+struct Cfg<'a> { name: String, level: i32, out: &'a mut i32, tag: &'a str }
+struct P { x: i32, y: i32 }
+fn origin() -> P { todo!() }
+fn main() {
+    let mut o = 0;
+    let name = String::from("a");
+    let level = 3;
+    let base = Cfg { name, level, out: &mut o, tag: "t" };
+    let c = Cfg { name: String::from("b"), ..base };
+    let q = P { x: 1, ..origin() };
+}
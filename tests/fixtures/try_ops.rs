@@ -0,0 +1,26 @@
+use std::fs::File;
+use std::io::{self, Read};
+
+fn read_file(path: &String) -> io::Result<String> {
+    let mut f = File::open(path)?;
+    let mut text = String::new();
+    f.read_to_string(&mut text)?;
+    Ok(text)
+}
+
+fn first(v: Option<Vec<i32>>) -> Option<i32> {
+    let items = v?;
+    Some(items[0])
+}
+
+fn main() -> Result<(), io::Error> {
+    let path = String::from("a.txt");
+    let text = read_file(&path)?;
+    println!("{}", text);
+    Ok(())
+}
+
+fn second(v: Option<i32>) -> Option<i32> {
+    let n = v?;
+    Some(n + 1)
+}
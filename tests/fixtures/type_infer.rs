@@ -0,0 +1,12 @@
+fn get_ref(x: &i32) -> &i32 {
+    x
+}
+fn main() {
+    let v = vec![1, 2];
+    let x = 3;
+    let first = v.first();
+    let s = get_ref(&x);
+    let it = v.iter();
+    let len = v.len();
+    println!("{:?} {} {:?} {}", first, s, it, len);
+}
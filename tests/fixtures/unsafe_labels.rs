@@ -0,0 +1,37 @@
+use std::ptr;
+
+fn main() {
+    let mut x = 5;
+    let p = &x as *const i32;
+    let q = ptr::addr_of_mut!(x);
+    unsafe {
+        let inner = String::from("in");
+        *q += *p;
+        println!("{}", inner);
+    }
+    let total = 'outer: loop {
+        let s = String::from("a");
+        loop {
+            let t = String::from("b");
+            if t.len() > 0 {
+                break 'outer 1;
+            }
+            continue 'outer;
+        }
+    };
+    let found = 'search: {
+        let w = String::from("c");
+        if w.is_empty() {
+            break 'search false;
+        }
+        true
+    };
+    for i in 0..2 {
+        let u = String::from("d");
+        if i == 0 {
+            continue;
+        }
+        println!("{}", u);
+    }
+    println!("{} {}", total, found);
+}